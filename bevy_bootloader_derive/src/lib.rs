@@ -0,0 +1,220 @@
+#![deny(
+    warnings,
+    missing_copy_implementations,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_import_braces,
+    unused_qualifications,
+    missing_docs
+)]
+
+//! Derive macros for the `bevy_bootloader` crate.
+//!
+//! This crate is not intended to be used directly. Instead, enable the `derive` feature of
+//! `bevy_bootloader` (enabled by default) and use the macros re-exported from there.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Fields, GenericArgument, Ident, Lit, LitStr, Meta,
+    NestedMeta, PathArguments, Result, Type,
+};
+
+/// Derive the `AssetCollection` trait for a struct whose fields are asset handles.
+///
+/// Each field must be annotated with `#[asset(path = "...")]`, and be of type `Handle<T>`.
+/// Fields annotated with `#[asset(path = "...", optional)]` are optional assets, and must be
+/// of type `Option<Handle<T>>`.
+///
+/// The derived `create()` checks that all the required assets are loaded and of the type of
+/// their field before taking any of them, so on error the loader is left untouched.
+#[proc_macro_derive(AssetCollection, attributes(asset))]
+pub fn derive_asset_collection(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    impl_asset_collection(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Asset field of a collection, parsed from its `#[asset(...)]` attribute.
+struct AssetField {
+    /// Name of the struct field.
+    ident: Ident,
+    /// Path of the asset to load.
+    path: LitStr,
+    /// Asset type `T` of the `Handle<T>` field.
+    asset_type: Type,
+    /// Is the asset optional?
+    optional: bool,
+}
+
+fn impl_asset_collection(input: &DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "AssetCollection can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "AssetCollection can only be derived for structs",
+            ))
+        }
+    };
+
+    let assets = fields
+        .iter()
+        .map(|field| parse_asset_field(field.ident.clone().unwrap(), &field.ty, &field.attrs))
+        .collect::<Result<Vec<_>>>()?;
+
+    let enqueue = assets.iter().map(|asset| {
+        let path = &asset.path;
+        if asset.optional {
+            quote! { loader.try_enqueue_optional(#path)?; }
+        } else {
+            quote! { loader.try_enqueue(#path)?; }
+        }
+    });
+
+    let check = assets.iter().map(|asset| {
+        let path = &asset.path;
+        let ty = &asset.asset_type;
+        let get = quote! {
+            loader.get_typed::<#ty>(#path, ::bevy_bootloader::collection_assets::<#ty>(world)?)
+        };
+        if asset.optional {
+            quote! {
+                if let Err(::bevy_bootloader::TakeError::TypeMismatch(mismatch)) = #get {
+                    return Err(::bevy_bootloader::TakeError::TypeMismatch(mismatch));
+                }
+            }
+        } else {
+            quote! { #get?; }
+        }
+    });
+
+    let create = assets.iter().map(|asset| {
+        let ident = &asset.ident;
+        let path = &asset.path;
+        let ty = &asset.asset_type;
+        let take = quote! {
+            loader.take_typed::<#ty>(#path, ::bevy_bootloader::collection_assets::<#ty>(world)?)
+        };
+        if asset.optional {
+            quote! { #ident: #take.ok() }
+        } else {
+            quote! { #ident: #take? }
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bevy_bootloader::AssetCollection for #name #ty_generics #where_clause {
+            fn enqueue(
+                loader: &mut ::bevy_bootloader::Loader,
+            ) -> ::std::result::Result<(), ::bevy_bootloader::LoaderError> {
+                #(#enqueue)*
+                Ok(())
+            }
+
+            fn create(
+                loader: &mut ::bevy_bootloader::Loader,
+                world: &::bevy::ecs::world::World,
+            ) -> ::std::result::Result<Self, ::bevy_bootloader::TakeError> {
+                #(#check)*
+                Ok(Self {
+                    #(#create,)*
+                })
+            }
+        }
+    })
+}
+
+fn parse_asset_field(ident: Ident, ty: &Type, attrs: &[syn::Attribute]) -> Result<AssetField> {
+    let mut path = None;
+    let mut optional = false;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("asset")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(Error::new_spanned(
+                    meta,
+                    "expected #[asset(path = \"...\")]",
+                ))
+            }
+        };
+        for nested in list.nested.iter() {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("path") => {
+                    match &nv.lit {
+                        Lit::Str(lit) => path = Some(lit.clone()),
+                        lit => return Err(Error::new_spanned(lit, "expected a string literal")),
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("optional") => optional = true,
+                nested => {
+                    return Err(Error::new_spanned(
+                        nested,
+                        "unknown asset attribute; expected `path = \"...\"` or `optional`",
+                    ))
+                }
+            }
+        }
+    }
+    let path = path.ok_or_else(|| {
+        Error::new(
+            ident.span(),
+            format!(
+                "missing #[asset(path = \"...\")] attribute on field `{}`",
+                ident
+            ),
+        )
+    })?;
+    let asset_type = if optional {
+        generic_argument(ty, "Option").and_then(|ty| generic_argument(ty, "Handle"))
+    } else {
+        generic_argument(ty, "Handle")
+    };
+    let asset_type = asset_type.cloned().ok_or_else(|| {
+        Error::new_spanned(
+            ty,
+            if optional {
+                "optional asset field must be of type Option<Handle<T>>"
+            } else {
+                "asset field must be of type Handle<T>"
+            },
+        )
+    })?;
+    Ok(AssetField {
+        ident,
+        path,
+        asset_type,
+        optional,
+    })
+}
+
+/// Get the single generic type argument `T` of a type `Name<T>` with the given name.
+fn generic_argument<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != name {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -84,8 +84,7 @@ impl AssetIo for MemoryAssetIo {
         &self,
         _path: &Path,
     ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
-        let asset_paths: Vec<PathBuf> =
-            self.assets.iter().map(|(k, _v)| PathBuf::from(k)).collect();
+        let asset_paths: Vec<PathBuf> = self.assets.keys().map(PathBuf::from).collect();
         Ok(Box::new(asset_paths.into_iter()))
     }
 
@@ -116,6 +115,9 @@ impl Plugin for DummyPlugin {
         source.add("file1.dummy", 0.2);
         source.add("file2.dummy", 7.5);
         source.add("file3.dummy", 3.1);
+        // Count the bytes read from the source, reported once boot completes
+        let source = ProgressAssetIo::new(source);
+        app.world.insert_resource(source.stats());
         let asset_server = AssetServer::with_boxed_io(Box::new(source), task_pool);
         //app.world.remove_resource::<AssetServer>();
         app.world.insert_resource(asset_server);
@@ -147,28 +149,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         })
         .add_asset::<DummyAsset>()
         .init_asset_loader::<DummyAssetLoader>()
+        .insert_resource(BootScreenStyle {
+            size: 200.,
+            thickness: 3.,
+            ..Default::default()
+        })
         .add_plugin(BootloaderPlugin)
-        .add_startup_system(setup_boot_screen)
-        .add_system(update_progress_bar)
+        .add_plugin(DefaultBootScreenPlugin)
+        .add_startup_system(setup_boot)
+        .add_system(report_bytes.after(BootSystem::UpdateBoot))
         .add_plugin(WorldInspectorPlugin::new())
         .run();
 
     Ok(())
 }
 
-#[derive(Component)]
-struct ProgressBar;
-
-/// Size of the progress bar, in pixels.
-const PROGRESS_BAR_SIZE: f32 = 200.0;
-
-/// Thickness of the progress bar, in pixels.
-const PROGRESS_BAR_THICKNESS: f32 = 3.0;
-
-fn setup_boot_screen(mut commands: Commands, mut clear_color: ResMut<ClearColor>) {
-    // Set clear color to background color
-    clear_color.0 = Color::rgba(0.1, 0.1, 0.1, 0.0);
-
+fn setup_boot(mut commands: Commands) {
     // Queue boot-time resources
     let mut loader = Loader::new();
     loader.enqueue("file1.dummy");
@@ -176,51 +172,18 @@ fn setup_boot_screen(mut commands: Commands, mut clear_color: ResMut<ClearColor>
     loader.enqueue("file3.dummy");
     loader.submit();
 
-    // Insert the boot bundle
+    // Insert the boot bundle. The DefaultBootScreenPlugin takes care of spawning a progress bar
+    // for it, and despawning it once boot completes.
     commands.spawn_bundle(BootBundle::new(loader));
-
-    // Spawn a camera to render the progress bar
-    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
-
-    // Spawn a progress bar formed of a background fixed sprite and a foreground sprite
-    // whose size will be animated to form the progress bar.
-    let color = Color::rgba(0.3, 0.4, 0.3, 1.0);
-    let background_color = Color::rgba(0.2, 0.3, 0.2, 1.0);
-    let size = Vec2::new(PROGRESS_BAR_SIZE, PROGRESS_BAR_THICKNESS);
-    commands.spawn_bundle(SpriteBundle {
-        sprite: Sprite {
-            color: background_color,
-            custom_size: Some(size),
-            ..Default::default()
-        },
-        ..Default::default()
-    });
-    commands
-        .spawn_bundle(SpriteBundle {
-            sprite: Sprite {
-                color,
-                custom_size: Some(Vec2::ZERO), // invisible initially
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(ProgressBar);
 }
 
-fn update_progress_bar(
-    boot_query: Query<&Boot>,
-    mut sprite_query: Query<(&mut Transform, &mut Sprite), With<ProgressBar>>,
-) {
-    if let Ok(boot) = boot_query.get_single() {
-        // Update the progress bar based on the fraction of assets already loaded, smoothed with
-        // a snappy animation to be visually pleasant without too much artifically delaying the
-        // boot sequence.
-        let smoothed_progress = boot.smoothed_progress();
-        let (mut transform, mut sprite) = sprite_query.single_mut();
-        let size = PROGRESS_BAR_SIZE * smoothed_progress;
-        // The sprite is a rect centered at the transform position, so move by half size to keep
-        // aligned to the left while width grows.
-        transform.translation.x = (size - PROGRESS_BAR_SIZE) / 2.;
-        sprite.custom_size = Some(Vec2::new(size, PROGRESS_BAR_THICKNESS));
+fn report_bytes(mut events: EventReader<BootCompleted>, stats: Res<AssetIoStats>) {
+    for event in events.iter() {
+        info!(
+            "Boot completed in {:?}, reading {} bytes for {} assets",
+            event.duration,
+            stats.total_bytes_read(),
+            event.asset_count
+        );
     }
 }
@@ -1,6 +1,10 @@
-use bevy::prelude::*;
+use bevy::{asset::Asset, ecs::entity::Entities, prelude::*, utils::Duration};
+use std::{collections::HashMap, fmt};
 
-use crate::loader::Loader;
+use crate::{
+    loader::{Eta, Loader, LoaderCancelled, TakeError, TypeMismatch},
+    sequence::{BootSequence, SequenceStatus},
+};
 
 /// Component for the boot sequence entity holding the [`Loader`] which handles
 /// the critical boot assets.
@@ -20,23 +24,67 @@ pub struct Boot {
     ///
     /// [`progress`]: Boot::progress
     smoothed_progress: f32,
-    /// Maximum progress speed, in percent per second. This is the maximum speed at which
-    /// [`smoothed_progress`] tries to catch up to [`progress`].
+    /// Velocity of [`smoothed_progress`], in progress per second, for smoothing strategies
+    /// which need it.
+    ///
+    /// [`smoothed_progress`]: Boot::smoothed_progress
+    velocity: f32,
+    /// Strategy used to calculate [`smoothed_progress`] from [`progress`].
     ///
     /// [`progress`]: Boot::progress
     /// [`smoothed_progress`]: Boot::smoothed_progress
-    speed: f32,
+    smoothing: ProgressSmoothing,
     /// Collection of entities of the boot screen, to delete once boot is done.
     entities: Vec<Entity>,
+    /// Did the boot sequence fail because some required asset failed to load?
+    failed: bool,
+    /// Time elapsed since the boot sequence started.
+    elapsed: Duration,
+    /// Optional identifier of the boot sequence, to tell apart concurrent boot sequences.
+    id: Option<BootId>,
+    /// Minimum duration of the boot sequence, if any.
+    min_duration: Option<Duration>,
+    /// Duration to hold the smoothed progress at 100% before completing, if any.
+    hold_at_full: Option<Duration>,
+    /// Value of [`elapsed`] when the smoothed progress reached 100%, if it did.
+    ///
+    /// [`elapsed`]: Boot::elapsed
+    full_since: Option<Duration>,
+    /// Did the boot sequence complete?
+    completed: bool,
+    /// Was the boot sequence cancelled?
+    cancelled: bool,
+    /// Index of the current phase, if the boot entity has a [`BootSequence`].
+    phase: Option<usize>,
+    /// Progress of the current phase.
+    phase_progress: f32,
+    /// Estimated time remaining until the boot sequence completes.
+    eta: Eta,
 }
 
+/// Smoothed progress above which the progress is considered full, to avoid waiting for
+/// asymptotic smoothing strategies to reach exactly 100%.
+const FULL_PROGRESS_THRESHOLD: f32 = 0.995;
+
 impl Default for Boot {
     fn default() -> Self {
         Boot {
             progress: 0.0,
             smoothed_progress: 0.0,
-            speed: 1.0, // percent per second; 1.0 = 100% in 1 second
+            velocity: 0.0,
+            smoothing: ProgressSmoothing::default(),
             entities: vec![],
+            failed: false,
+            elapsed: Duration::ZERO,
+            id: None,
+            min_duration: None,
+            hold_at_full: None,
+            full_since: None,
+            completed: false,
+            cancelled: false,
+            phase: None,
+            phase_progress: 0.0,
+            eta: Eta::default(),
         }
     }
 }
@@ -50,6 +98,9 @@ impl Boot {
     /// Update the boot progress based on the actual `progress` in \[0:1\] and the current
     /// frame delta time in seconds (for progress smoothing).
     ///
+    /// The progress never goes backward; a `progress` lower than the current one is ignored,
+    /// and only advances the smoothing.
+    ///
     /// This is called automatically by the default update system, [`update_boot()`], based on
     /// the progress reported by the associated [`Loader`].
     ///
@@ -66,10 +117,35 @@ impl Boot {
     /// }
     /// ```
     pub fn set_progress(&mut self, progress: f32, dt: f32) {
-        self.progress = progress.clamp(0.0, 1.0);
-        let delta_p = (self.progress - self.smoothed_progress) / self.speed;
-        let smoothed_progress = self.smoothed_progress + dt * delta_p;
-        self.smoothed_progress = smoothed_progress.min(self.progress);
+        // Never go backward, for example when assets are appended to the loader batch
+        self.progress = progress.clamp(0.0, 1.0).max(self.progress);
+        let (smoothed_progress, velocity) = self.smoothing.step(
+            self.smoothed_progress,
+            self.velocity,
+            self.progress,
+            dt.max(0.0),
+        );
+        if smoothed_progress >= self.progress {
+            // Never overshoot the actual progress; stop there
+            self.smoothed_progress = self.progress;
+            self.velocity = 0.0;
+        } else {
+            self.smoothed_progress = smoothed_progress;
+            self.velocity = velocity;
+        }
+    }
+
+    /// Get the strategy used to smooth the progress.
+    pub fn smoothing(&self) -> ProgressSmoothing {
+        self.smoothing
+    }
+
+    /// Set the strategy used to smooth the progress.
+    ///
+    /// See [`ProgressSmoothing`] for details.
+    pub fn set_smoothing(&mut self, smoothing: ProgressSmoothing) {
+        self.smoothing = smoothing;
+        self.velocity = 0.0;
     }
 
     /// Get the actual loading progress, in \[0:1\].
@@ -121,6 +197,251 @@ impl Boot {
     pub fn smoothed_progress(&self) -> f32 {
         self.smoothed_progress
     }
+
+    /// Get the index of the current phase, if the boot entity has a [`BootSequence`] which
+    /// didn't complete yet.
+    ///
+    /// This is updated by the default update system, [`update_boot()`].
+    pub fn phase(&self) -> Option<usize> {
+        self.phase
+    }
+
+    /// Get the actual progress of the current phase, in \[0:1\].
+    ///
+    /// If the boot entity has a [`BootSequence`], this is the progress of its current phase, while
+    /// [`progress()`] is the overall progress of all phases. Otherwise this is equal to
+    /// [`progress()`].
+    ///
+    /// [`progress()`]: Boot::progress
+    pub fn phase_progress(&self) -> f32 {
+        self.phase_progress
+    }
+
+    /// Get the estimated time remaining until all the assets of the boot sequence are loaded.
+    ///
+    /// The estimate is based on the time taken so far to reach the current [`progress()`],
+    /// and is smoothed over time to avoid sudden jumps. This is `None` until some progress
+    /// was made, and zero once all assets are loaded, even if the boot sequence itself waits
+    /// for its [`min_duration()`] or [`hold_at_full()`] before completing.
+    ///
+    /// This is updated by the default update system, [`update_boot()`].
+    ///
+    /// [`progress()`]: Boot::progress
+    /// [`min_duration()`]: Boot::min_duration
+    /// [`hold_at_full()`]: Boot::hold_at_full
+    pub fn estimated_remaining(&self) -> Option<Duration> {
+        self.eta.remaining()
+    }
+
+    /// Did the boot sequence fail?
+    ///
+    /// With the default update system, [`update_boot()`], the boot sequence fails when the
+    /// associated [`Loader`] batch fails, that is when some required asset failed to load. The
+    /// boot entity is then left in place, allowing the app to inspect the [`Loader`] and for
+    /// example display an error screen.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_bootloader::*;
+    /// fn check_boot(query: Query<(&Boot, &Loader)>) {
+    ///     if let Ok((boot, loader)) = query.get_single() {
+    ///         if boot.is_failed() {
+    ///             error!("Failed to load: {:?}", loader.failed_paths());
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Register an entity of the boot screen, to despawn once the boot sequence completes.
+    ///
+    /// The entity is despawned recursively, along with all its children. Alternatively, add
+    /// the [`BootScreen`] marker component to the entity.
+    pub fn add_entity(&mut self, entity: Entity) {
+        self.entities.push(entity);
+    }
+
+    /// Get the entities of the boot screen registered with [`add_entity()`].
+    ///
+    /// [`add_entity()`]: Boot::add_entity
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Get the identifier of the boot sequence, if any.
+    pub fn id(&self) -> Option<&BootId> {
+        self.id.as_ref()
+    }
+
+    /// Set the identifier of the boot sequence.
+    ///
+    /// The identifier is reported in the [`BootCompleted`] and [`BootFailed`] events, and allows
+    /// associating [`BootScreen`] entities with a specific boot sequence when several of them
+    /// run concurrently.
+    pub fn set_id(&mut self, id: impl Into<BootId>) {
+        self.id = Some(id.into());
+    }
+
+    /// Get the time elapsed since the boot sequence started.
+    ///
+    /// This is updated by the default update system, [`update_boot()`], and stops once the boot
+    /// sequence completes or fails.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Get the minimum duration of the boot sequence, if any.
+    pub fn min_duration(&self) -> Option<Duration> {
+        self.min_duration
+    }
+
+    /// Set the minimum duration of the boot sequence.
+    ///
+    /// The default update system, [`update_boot()`], doesn't complete the boot sequence before
+    /// this duration elapsed, even if the [`Loader`] is done. This avoids the boot screen
+    /// flashing for a single frame when loading is very fast. Once loading is done, the smoothed
+    /// progress catches up to 100% until this duration elapsed, then snaps to 100% if not there
+    /// yet, so a slow smoothing doesn't delay the boot sequence any further.
+    pub fn set_min_duration(&mut self, min_duration: Option<Duration>) {
+        self.min_duration = min_duration;
+    }
+
+    /// Get the duration to hold the smoothed progress at 100% before completing, if any.
+    pub fn hold_at_full(&self) -> Option<Duration> {
+        self.hold_at_full
+    }
+
+    /// Set the duration to hold the smoothed progress at 100% before completing.
+    ///
+    /// Once the [`Loader`] is done, the default update system, [`update_boot()`], lets the
+    /// smoothed progress reach 100%, snapping it there as soon as the minimum duration, if any,
+    /// elapsed (see [`set_min_duration()`]). It then waits for this extra duration before
+    /// completing the boot sequence.
+    ///
+    /// [`set_min_duration()`]: Boot::set_min_duration
+    pub fn set_hold_at_full(&mut self, hold_at_full: Option<Duration>) {
+        self.hold_at_full = hold_at_full;
+    }
+
+    /// Did the boot sequence complete?
+    ///
+    /// With the default update system, [`update_boot()`], this is set once the boot sequence
+    /// completes, just before the boot entity is despawned at the end of the current stage.
+    pub fn is_completed(&self) -> bool {
+        self.completed
+    }
+
+    /// Was the boot sequence cancelled?
+    ///
+    /// With the default update system, [`update_boot()`], this is set once the batch of the
+    /// [`Loader`] is cancelled with [`Loader::cancel()`], just before the boot entity is
+    /// despawned at the end of the current stage.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// Update the boot sequence once loading is done, and check if it can complete, that is
+    /// if the minimum duration and hold time elapsed.
+    fn update_done(&mut self, dt: f32) -> bool {
+        if self.min_duration.is_none() && self.hold_at_full.is_none() {
+            return true;
+        }
+
+        // Let the smoothed progress catch up to 100%, but don't wait for it past the minimum
+        // duration, which with a slow smoothing could take several seconds.
+        self.set_progress(1.0, dt);
+        let min_elapsed = self.min_duration.is_none_or(|min| self.elapsed >= min);
+        if min_elapsed || self.smoothed_progress >= FULL_PROGRESS_THRESHOLD {
+            self.smoothed_progress = 1.0;
+            self.full_since.get_or_insert(self.elapsed);
+        }
+
+        let hold_elapsed = self.full_since.is_some_and(|full_since| {
+            self.elapsed - full_since >= self.hold_at_full.unwrap_or_default()
+        });
+        min_elapsed && hold_elapsed
+    }
+}
+
+/// Strategy to smooth the progress of a [`Boot`].
+///
+/// The smoothed progress, returned by [`Boot::smoothed_progress()`], follows the actual progress
+/// reported by [`Boot::progress()`] according to this strategy, and never exceeds it. All
+/// strategies are frame-rate independent: updating the progress once with a given delta time or
+/// several times with smaller delta times summing up to the same value yields the same smoothed
+/// progress, provided the actual progress doesn't change in between.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_bootloader::*;
+/// let mut boot = Boot::new();
+/// boot.set_smoothing(ProgressSmoothing::Linear { speed: 0.5 });
+/// boot.set_progress(1.0, 1.0);
+/// assert_eq!(boot.smoothed_progress(), 0.5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgressSmoothing {
+    /// No smoothing; the smoothed progress is always equal to the actual progress.
+    None,
+    /// Exponential decay toward the actual progress. The gap to the actual progress is divided by
+    /// `e` (about 2.7) each `time_constant` seconds. This is the default, with a time constant of
+    /// one second.
+    Exponential {
+        /// Time constant of the decay, in seconds. A non-positive value disables smoothing.
+        time_constant: f32,
+    },
+    /// Constant-rate fill toward the actual progress, at a maximum speed.
+    Linear {
+        /// Maximum speed, in progress per second; `1.0` fills the entire progress in one second.
+        /// A non-positive value disables smoothing.
+        speed: f32,
+    },
+    /// Critically damped spring toward the actual progress, which starts slowly and eases in
+    /// without oscillating.
+    Spring {
+        /// Time constant of the spring, in seconds, which is the inverse of its natural
+        /// frequency. A non-positive value disables smoothing.
+        time_constant: f32,
+    },
+}
+
+impl Default for ProgressSmoothing {
+    fn default() -> Self {
+        ProgressSmoothing::Exponential { time_constant: 1.0 }
+    }
+}
+
+impl ProgressSmoothing {
+    /// Advance the smoothed progress `value` with its `velocity` toward `target` by `dt` seconds,
+    /// and return the new value and velocity.
+    fn step(&self, value: f32, velocity: f32, target: f32, dt: f32) -> (f32, f32) {
+        match *self {
+            ProgressSmoothing::Exponential { time_constant } if time_constant > 0.0 => {
+                let decay = (-dt / time_constant).exp();
+                (target + (value - target) * decay, 0.0)
+            }
+            ProgressSmoothing::Linear { speed } if speed > 0.0 => {
+                ((value + speed * dt).min(target), 0.0)
+            }
+            ProgressSmoothing::Spring { time_constant } if time_constant > 0.0 => {
+                // Exact solution of the critically damped spring, assuming the target doesn't
+                // change during the time step.
+                let omega = 1.0 / time_constant;
+                let x0 = value - target;
+                let c = velocity + omega * x0;
+                let decay = (-omega * dt).exp();
+                let x = (x0 + c * dt) * decay;
+                let v = (velocity - omega * c * dt) * decay;
+                (target + x, v)
+            }
+            _ => (target, 0.0),
+        }
+    }
 }
 
 /// Bundle with a [`Boot`] helper and its associated [`Loader`].
@@ -140,63 +461,927 @@ impl BootBundle {
             loader,
         }
     }
+
+    /// Register some entities of the boot screen, to despawn once the boot sequence completes.
+    ///
+    /// See [`Boot::add_entity()`] for details.
+    pub fn with_entities(mut self, entities: impl IntoIterator<Item = Entity>) -> Self {
+        self.boot.entities.extend(entities);
+        self
+    }
+
+    /// Set the strategy used to smooth the progress.
+    ///
+    /// See [`ProgressSmoothing`] for details.
+    pub fn with_smoothing(mut self, smoothing: ProgressSmoothing) -> Self {
+        self.boot.set_smoothing(smoothing);
+        self
+    }
+
+    /// Set the minimum duration of the boot sequence.
+    ///
+    /// See [`Boot::set_min_duration()`] for details.
+    pub fn with_min_duration(mut self, min_duration: Duration) -> Self {
+        self.boot.set_min_duration(Some(min_duration));
+        self
+    }
+
+    /// Set the duration to hold the smoothed progress at 100% before completing.
+    ///
+    /// See [`Boot::set_hold_at_full()`] for details.
+    pub fn with_hold_at_full(mut self, hold_at_full: Duration) -> Self {
+        self.boot.set_hold_at_full(Some(hold_at_full));
+        self
+    }
+
+    /// Set the identifier of the boot sequence.
+    ///
+    /// See [`Boot::set_id()`] for details.
+    pub fn with_id(mut self, id: impl Into<BootId>) -> Self {
+        self.boot.set_id(id);
+        self
+    }
 }
 
-/// Update the [`Boot`] progress based on its [`Loader`] completion state, and despawn
-/// the entity holding them once done.
+/// Identifier of a boot sequence.
 ///
-/// The [`Boot`] and [`Loader`] components must be on the same entity, and there must
-/// be only one such entity. The simplest way is to use a [`BootBundle`].
+/// This allows telling apart several boot sequences running concurrently, for example one per
+/// window or per split-screen viewport. The identifier of a [`Boot`] is set with
+/// [`Boot::set_id()`] or [`BootBundle::with_id()`], and reported in the [`BootCompleted`] and
+/// [`BootFailed`] events.
 ///
-/// This system is automatically added to the app when adding the [`BootloaderPlugin`] plugin.
+/// This is also a component. Adding it alongside a [`BootScreen`] marker associates the boot
+/// screen entity with the boot sequence of the same identifier, so that the entity is despawned
+/// only once that specific boot sequence completes.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_bootloader::*;
+/// fn setup_boot(mut commands: Commands) {
+///     let mut loader = Loader::new();
+///     loader.enqueue("left/level.gltf");
+///     loader.submit();
+///     commands.spawn_bundle(BootBundle::new(loader).with_id("left"));
+///     commands
+///         .spawn_bundle(OrthographicCameraBundle::new_2d())
+///         .insert(BootScreen)
+///         .insert(BootId::from("left"));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Component)]
+pub struct BootId(pub String);
+
+impl BootId {
+    /// Create a new identifier.
+    pub fn new(id: impl Into<String>) -> Self {
+        BootId(id.into())
+    }
+
+    /// Get the identifier as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for BootId {
+    fn from(id: &str) -> Self {
+        BootId(id.to_string())
+    }
+}
+
+impl From<String> for BootId {
+    fn from(id: String) -> Self {
+        BootId(id)
+    }
+}
+
+impl fmt::Display for BootId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Marker component for the entities of the boot screen.
+///
+/// All entities with this component are despawned recursively, along with their children,
+/// by the default update system, [`update_boot()`], once the boot sequence completes. This
+/// is an alternative to registering individual entities with [`Boot::add_entity()`].
+///
+/// When several boot sequences run concurrently, an entity with both this marker and a
+/// [`BootId`] component is despawned once the boot sequence with the same identifier completes.
+/// An entity without [`BootId`] is despawned once no boot sequence remains.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_bootloader::*;
+/// fn setup_boot_screen(mut commands: Commands) {
+///     // Spawn a camera for the boot screen, which will be despawned once boot completes
+///     commands
+///         .spawn_bundle(OrthographicCameraBundle::new_2d())
+///         .insert(BootScreen);
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct BootScreen;
+
+/// Event sent by [`update_boot()`] when a boot sequence completes successfully.
+///
+/// The event is sent the frame the boot entity is despawned, allowing other systems to react to
+/// the boot completion without having to poll the [`Boot`] and [`Loader`] components.
+#[derive(Debug, Clone)]
+pub struct BootCompleted {
+    /// The boot entity, which is despawned at the end of the current stage.
+    pub entity: Entity,
+    /// The identifier of the boot sequence, if any.
+    pub id: Option<BootId>,
+    /// Total duration of the boot sequence.
+    pub duration: Duration,
+    /// Number of assets loaded successfully by the boot sequence, including those already
+    /// taken. Tasks and optional assets which failed to load are not counted.
+    pub asset_count: usize,
+    /// Handles of the assets loaded by the boot sequence and not already taken, keyed by
+    /// asset path. The same handles are also stored in the [`BootAssets`] resource.
+    pub handles: HashMap<String, HandleUntyped>,
+}
+
+/// Event sent by [`update_boot()`] when a boot sequence fails because some required asset
+/// failed to load.
+#[derive(Debug, Clone)]
+pub struct BootFailed {
+    /// The boot entity.
+    pub entity: Entity,
+    /// The identifier of the boot sequence, if any.
+    pub id: Option<BootId>,
+    /// Paths of all the assets which failed to load, including optional ones.
+    pub failed_paths: Vec<String>,
+    /// Name of the phase which failed, if the boot entity has a [`BootSequence`].
+    pub phase: Option<String>,
+}
+
+/// Event sent by [`update_boot()`] when a boot sequence is cancelled, because the batch of its
+/// [`Loader`] was cancelled with [`Loader::cancel()`].
+#[derive(Debug, Clone)]
+pub struct BootCancelled {
+    /// The boot entity, which is despawned at the end of the current stage.
+    pub entity: Entity,
+    /// The identifier of the boot sequence, if any.
+    pub id: Option<BootId>,
+    /// Duration of the boot sequence until it was cancelled.
+    pub duration: Duration,
+}
+
+/// Label of the boot systems, for ordering other systems relative to them.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemLabel)]
+pub enum BootSystem {
+    /// The [`update_boot()`] system.
+    UpdateBoot,
+}
+
+/// Resource holding the assets loaded during boot.
+///
+/// When the boot sequence completes, the default update system, [`update_boot()`], moves all the
+/// assets loaded by the [`Loader`] and not yet taken into this resource, before despawning the boot
+/// entity. This keeps those assets alive, and allows game code to fetch them at any later time.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_bootloader::*;
+/// fn setup_menu(boot_assets: Res<BootAssets>, images: Res<Assets<Image>>) {
+///     if let Ok(logo) = boot_assets.get_typed::<Image>("logo.png", &images) {
+///         // ...
+///     }
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct BootAssets {
+    /// Asset handles, keyed by asset path.
+    handles: HashMap<String, HandleUntyped>,
+}
+
+impl BootAssets {
+    /// Get the handle of the boot asset with the given path, if any.
+    pub fn get(&self, path: &str) -> Option<&HandleUntyped> {
+        self.handles.get(path)
+    }
+
+    /// Get a new strong handle to the boot asset with the given path, after checking that the asset
+    /// is of the requested type `T` by looking it up in the [`Assets<T>`] storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TakeError::NotFound`] if there is no boot asset with the given path, and
+    /// [`TakeError::TypeMismatch`] if the asset is not of type `T`.
+    pub fn get_typed<T: Asset>(
+        &self,
+        path: &str,
+        assets: &Assets<T>,
+    ) -> Result<Handle<T>, TakeError> {
+        match self.handles.get(path) {
+            Some(handle) if assets.contains(handle.id) => Ok(handle.clone().typed::<T>()),
+            Some(_) => Err(TakeError::TypeMismatch(TypeMismatch::of::<T>())),
+            None => Err(TakeError::NotFound),
+        }
+    }
+
+    /// Take the handle of the boot asset with the given path, if any, and remove it from the
+    /// resource. The asset may get unloaded once all other handles to it are dropped.
+    pub fn take(&mut self, path: &str) -> Option<HandleUntyped> {
+        self.handles.remove(path)
+    }
+
+    /// Check if a boot asset with the given path exists.
+    pub fn contains(&self, path: &str) -> bool {
+        self.handles.contains_key(path)
+    }
+
+    /// Iterate over all the boot assets, as pairs of asset path and handle.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &HandleUntyped)> {
+        self.handles
+            .iter()
+            .map(|(path, handle)| (&path[..], handle))
+    }
+
+    /// Number of boot assets.
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Is the collection of boot assets empty?
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// Remove all boot assets. Those assets may get unloaded once all other handles to them are
+    /// dropped.
+    pub fn clear(&mut self) {
+        self.handles.clear();
+    }
+}
+
+/// Update the progress of all [`Boot`] entities based on their [`Loader`] completion state, and
+/// despawn each entity once done.
+///
+/// A boot sequence is done once its [`Loader`] is done, or if the entity has a [`BootSequence`]
+/// once all its phases completed, and its minimum duration and hold time, if any, elapsed (see
+/// [`Boot::set_min_duration()`] and [`Boot::set_hold_at_full()`]).
 ///
-/// # Panics
+/// Once a boot sequence is done, the entities of its boot screen registered with
+/// [`Boot::add_entity()`] are despawned recursively too, as well as the entities marked with the
+/// [`BootScreen`] component and the same [`BootId`]. The entities marked with [`BootScreen`] but
+/// without [`BootId`] are despawned once the last boot sequence completes or is cancelled.
 ///
-/// This system panics if there is more than one entity with both a [`Boot`] and a [`Loader`]
-/// components.
+/// Before despawning the entity, all the assets loaded by the [`Loader`] and not yet taken are
+/// moved into the [`BootAssets`] resource, if it exists. Otherwise they're only kept alive by the
+/// [`BootCompleted`] event, and may get unloaded once it's dropped.
+///
+/// If the [`Loader`] batch fails because some required asset failed to load, or any phase of the
+/// [`BootSequence`] fails, the [`Boot`] is marked as failed (see [`Boot::is_failed()`]) and the entity is not despawned.
+///
+/// If the [`Loader`] batch is cancelled with [`Loader::cancel()`], the boot sequence ends there:
+/// the [`Boot`] is marked as cancelled (see [`Boot::is_cancelled()`]), and the entity and its boot
+/// screen are despawned like on completion, without moving any asset into [`BootAssets`]. The
+/// [`LoaderCancelled`] event of the batch is sent before despawning, if not already sent.
+///
+/// On completion, a [`BootCompleted`] event is sent. On failure, a [`BootFailed`] event is
+/// sent. On cancellation, a [`BootCancelled`] event is sent. All those events, as well as the
+/// [`LoaderCancelled`] event, must be registered with the app, otherwise the system panics.
+/// The [`BootloaderPlugin`] registers them all; when adding this system manually, register
+/// them with [`App::add_event()`], or add the [`LoaderPlugin`] for [`LoaderCancelled`].
+///
+/// The [`Boot`] and [`Loader`] components must be on the same entity. The simplest way is to use
+/// a [`BootBundle`]. Any number of such entities can exist at once, each being updated
+/// independently. Use [`BootId`] to tell them apart.
+///
+/// This system is automatically added to the app when adding the [`BootloaderPlugin`] plugin.
+/// To also transition the app state once boot completes, use [`BootloaderPlugin::with_states()`].
 ///
 /// [`BootloaderPlugin`]: crate::BootloaderPlugin
+/// [`LoaderPlugin`]: crate::LoaderPlugin
+/// [`BootloaderPlugin::with_states()`]: crate::BootloaderPlugin::with_states
+#[allow(clippy::too_many_arguments)]
 pub fn update_boot(
     time: Res<Time>,
     mut commands: Commands,
-    mut query: Query<(Entity, &mut Loader, &mut Boot)>,
+    mut boot_assets: Option<ResMut<BootAssets>>,
+    entities: &Entities,
+    mut query: Query<(Entity, &mut Loader, &mut Boot, Option<&mut BootSequence>)>,
+    screen_query: Query<(Entity, Option<&BootId>), With<BootScreen>>,
+    mut completed_events: EventWriter<BootCompleted>,
+    mut failed_events: EventWriter<BootFailed>,
+    mut cancelled_events: EventWriter<BootCancelled>,
+    mut loader_cancelled_events: EventWriter<LoaderCancelled>,
 ) {
-    if let Ok((id, loader, mut boot)) = query.get_single_mut() {
-        if loader.is_done() {
-            // Mark the Boot entity for later destruction (at the end of the stage)
-            commands.entity(id).despawn();
-
-            // Also delete all related entities for the boot screen
-            for id in &boot.entities {
-                commands.entity(*id).despawn();
+    let mut any_completed = false;
+    let mut any_remaining = false;
+    for (id, mut loader, mut boot, mut sequence) in query.iter_mut() {
+        if loader.is_cancelled() {
+            boot.cancelled = true;
+            info!(
+                "Boot{} cancelled after {:?}",
+                display_id(&boot.id),
+                boot.elapsed
+            );
+            cancelled_events.send(BootCancelled {
+                entity: id,
+                id: boot.id.clone(),
+                duration: boot.elapsed,
+            });
+            // The loader system won't see the entity again once despawned
+            loader.notify_cancelled(id, &mut loader_cancelled_events);
+            despawn_boot(&mut commands, entities, &screen_query, id, &boot);
+            any_completed = true;
+            continue;
+        }
+
+        if !boot.failed {
+            boot.elapsed += time.delta();
+        }
+
+        // A boot entity without sequence behaves like a sequence with a single loader batch
+        let status = match sequence.as_deref_mut() {
+            Some(_) if boot.failed => SequenceStatus::Failed,
+            Some(sequence) => sequence.update(&mut loader),
+            None if loader.is_done() => SequenceStatus::Done,
+            None if loader.is_batch_failed() => SequenceStatus::Failed,
+            None => {
+                let progress = target_progress(&loader);
+                SequenceStatus::InProgress {
+                    progress,
+                    phase_progress: progress,
+                }
             }
+        };
 
-            // TODO -- use resources?
+        let done = matches!(status, SequenceStatus::Done);
+        if done && boot.update_done(time.delta_seconds()) {
+            boot.completed = true;
 
-            // Change app state to transition to the main menu
-            //assert!(*state.current() == AppState::Boot);
-            //state.set(AppState::MainMenu).unwrap();
-        } else {
-            // Calculate the upper progress ratio. Traditionally one would calculate the current ratio of
-            // completed work, that is the number of assets loaded over the total number that needs to be
-            // loaded. This ratio would only reach 1.0 (100%) once all assets are loaded, and therefore
-            // once the boot sequence is done and likely the boot screen disappears. This means the progress
-            // bar would never reach 100%. Instead, calculate the upper bound of the ratio, which is the
-            // ratio of completed items plus one, accounting for the fact one item is currently being loaded.
-            // This means the progress bar will reach (N-1)/N once the last asset remains, and will smoothly
-            // get close to 1.0 (100%) from there. In theory this ratio would go over 1.0 once the last
-            // asset is loaded, but at this point we transition to another screen so we don't care.
-            let total = loader.total_count();
-            let remain = loader.pending_count();
-            let upper_ratio = if total > 0 && remain < total {
-                (total - remain + 1) as f32 / total as f32
-            } else {
-                1.0
+            // Keep alive all boot assets not yet taken, which would otherwise be dropped with the Loader
+            let (mut handles, asset_count) = match sequence.as_deref_mut() {
+                Some(sequence) => (sequence.take_handles(), sequence.asset_count()),
+                None => (HashMap::new(), loader.loaded_count()),
             };
-            // Update the progress bar based on the fraction of assets already loaded, smoothed with
-            // a snappy animation to be visually pleasant without too much artifically delaying the
-            // boot sequence.
-            boot.set_progress(upper_ratio, time.delta_seconds());
+            handles.extend(loader.take_all());
+            if let Some(boot_assets) = boot_assets.as_deref_mut() {
+                boot_assets.handles.extend(handles.clone());
+            }
+
+            info!(
+                "Boot{} completed in {:?} ({} assets)",
+                display_id(&boot.id),
+                boot.elapsed,
+                asset_count
+            );
+            completed_events.send(BootCompleted {
+                entity: id,
+                id: boot.id.clone(),
+                duration: boot.elapsed,
+                asset_count,
+                handles,
+            });
+
+            despawn_boot(&mut commands, entities, &screen_query, id, &boot);
+            any_completed = true;
+            continue;
+        }
+
+        any_remaining = true;
+        match status {
+            SequenceStatus::Done => {
+                // Waiting for the minimum duration or hold time to elapse
+                boot.phase = None;
+                boot.eta.complete();
+            }
+            SequenceStatus::Failed => {
+                if !boot.failed {
+                    let failed_paths = loader.failed_paths();
+                    let phase = sequence
+                        .as_deref()
+                        .and_then(BootSequence::current_phase)
+                        .map(|phase| phase.name().to_string());
+                    match &phase {
+                        Some(phase) => error!(
+                            "Boot{} failed in phase '{}': failed assets: {:?}",
+                            display_id(&boot.id),
+                            phase,
+                            failed_paths
+                        ),
+                        None => error!(
+                            "Boot{} failed: some required assets failed to load: {:?}",
+                            display_id(&boot.id),
+                            failed_paths
+                        ),
+                    }
+                    boot.failed = true;
+                    failed_events.send(BootFailed {
+                        entity: id,
+                        id: boot.id.clone(),
+                        failed_paths,
+                        phase,
+                    });
+                }
+            }
+            SequenceStatus::InProgress {
+                progress,
+                phase_progress,
+            } => {
+                boot.phase = sequence.as_deref().and_then(BootSequence::current_index);
+                boot.phase_progress = phase_progress;
+                // Estimate the remaining time from the actual completed work, without the extra
+                // item accounted for by the upper progress of a single loader batch.
+                let completed = if sequence.is_some() {
+                    progress
+                } else {
+                    loader.progress()
+                };
+                let elapsed = boot.elapsed;
+                boot.eta.update(completed, elapsed);
+                // Update the progress bar based on the fraction of assets already loaded, smoothed with
+                // a snappy animation to be visually pleasant without too much artifically delaying the
+                // boot sequence.
+                boot.set_progress(progress, time.delta_seconds());
+            }
+        }
+    }
+
+    // Delete the boot screen entities shared by all boot sequences once the last one ends
+    if any_completed && !any_remaining {
+        for (entity, screen_id) in screen_query.iter() {
+            if screen_id.is_none() && entities.contains(entity) {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+/// Calculate the upper progress ratio of a loader batch.
+///
+/// Traditionally one would calculate the current ratio of completed work, that is the weight of
+/// the assets loaded over the total weight of the assets that need to be loaded. This ratio would
+/// only reach 1.0 (100%) once all assets are loaded, and therefore once the boot sequence is done
+/// and likely the boot screen disappears. This means the progress bar would never reach 100%.
+/// Instead, calculate the upper bound of the ratio, which is the ratio of completed work plus the
+/// work of one more item, accounting for the fact one item is currently being loaded. The smallest
+/// pending item is used, which keeps the ratio from going backward as items complete. This means
+/// the progress bar will reach 1.0 (100%) once the last asset remains, and the smoothed progress
+/// will get close to it from there.
+pub(crate) fn upper_progress(loader: &Loader) -> f32 {
+    let remain = loader.pending_count();
+    if remain == 0 {
+        return 1.0;
+    }
+    // Items enqueued but not yet submitted are pending without counting toward the total
+    let total = loader.total_count();
+    if total == 0 || remain > total {
+        return 0.0;
+    }
+    let total_weight = loader.total_weight();
+    if total_weight > 0.0 {
+        let done_weight = total_weight - loader.pending_weight();
+        ((done_weight + loader.min_pending_weight()) / total_weight).min(1.0)
+    } else {
+        (total - remain + 1) as f32 / total as f32
+    }
+}
+
+/// Calculate the progress ratio of a loader batch to display as the boot progress.
+///
+/// When the expected duration of the batch is known from a previous run, this is the time-based
+/// progress of the loader, which advances linearly with time, capped to the upper progress.
+/// Otherwise this is the upper progress (see [`upper_progress()`]).
+pub(crate) fn target_progress(loader: &Loader) -> f32 {
+    let upper = upper_progress(loader);
+    if loader.expected_duration().is_some() {
+        loader.time_progress().min(upper)
+    } else {
+        upper
+    }
+}
+
+/// Despawn a boot entity which ended, and the entities of its boot screen.
+fn despawn_boot(
+    commands: &mut Commands,
+    entities: &Entities,
+    screen_query: &Query<(Entity, Option<&BootId>), With<BootScreen>>,
+    id: Entity,
+    boot: &Boot,
+) {
+    // Mark the Boot entity for later destruction (at the end of the stage)
+    commands.entity(id).despawn_recursive();
+
+    // Also delete all related entities for the boot screen, skipping any entity already
+    // despawned by the user.
+    let screen_entities = screen_query
+        .iter()
+        .filter(|(_, screen_id)| screen_id.is_some() && *screen_id == boot.id.as_ref())
+        .map(|(entity, _)| entity);
+    for entity in boot.entities.iter().copied().chain(screen_entities) {
+        if entities.contains(entity) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Format an optional boot identifier for logging.
+fn display_id(id: &Option<BootId>) -> String {
+    match id {
+        Some(id) => format!(" '{}'", id),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{test_app, update_until, TestAsset},
+        BootloaderPlugin,
+    };
+    use bevy::{app::Events, ecs::system::Resource};
+    use futures_lite::future;
+
+    #[test]
+    fn hold() {
+        // Without minimum duration nor hold time, complete as soon as loading is done
+        let mut boot = Boot::new();
+        assert!(boot.update_done(0.0));
+
+        let mut boot = Boot::new();
+        boot.set_smoothing(ProgressSmoothing::Linear { speed: 1.0 });
+        boot.set_min_duration(Some(Duration::from_secs(2)));
+        boot.set_hold_at_full(Some(Duration::from_millis(500)));
+        let dt = Duration::from_millis(100);
+        let mut frames = 0;
+        loop {
+            boot.elapsed += dt;
+            frames += 1;
+            if boot.update_done(dt.as_secs_f32()) {
+                break;
+            }
+            assert!(frames < 100);
+        }
+        // Reached 100% after 1s, held for 0.5s, but the minimum duration is 2s
+        assert_eq!(boot.smoothed_progress(), 1.0);
+        assert_eq!(boot.elapsed(), Duration::from_secs(2));
+
+        // The hold time starts only once the smoothed progress visibly reached 100%, which
+        // without minimum duration is right away
+        boot.set_min_duration(None);
+        boot.set_hold_at_full(Some(Duration::from_secs(1)));
+        boot.full_since = None;
+        boot.smoothed_progress = 0.5;
+        boot.elapsed = Duration::ZERO;
+        loop {
+            boot.elapsed += dt;
+            if boot.update_done(dt.as_secs_f32()) {
+                break;
+            }
+        }
+        // Snapped to 100% on the first frame, then held for 1s
+        assert_eq!(boot.elapsed(), Duration::from_millis(1100));
+
+        // With the default smoothing, which takes about 5s to catch up, the minimum duration
+        // isn't extended
+        let mut boot = Boot::new();
+        boot.set_min_duration(Some(Duration::from_secs(2)));
+        boot.set_progress(0.5, 0.0);
+        loop {
+            boot.elapsed += dt;
+            if boot.update_done(dt.as_secs_f32()) {
+                break;
+            }
+            assert!(boot.smoothed_progress() < 1.0);
+        }
+        assert_eq!(boot.smoothed_progress(), 1.0);
+        assert_eq!(boot.elapsed(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn smoothing() {
+        let strategies = [
+            ProgressSmoothing::None,
+            ProgressSmoothing::Exponential { time_constant: 0.5 },
+            ProgressSmoothing::Linear { speed: 0.5 },
+            ProgressSmoothing::Spring { time_constant: 0.5 },
+        ];
+        for smoothing in strategies {
+            // One large step and many small steps give the same result
+            let mut coarse = Boot::new();
+            coarse.set_smoothing(smoothing);
+            coarse.set_progress(0.8, 1.0);
+            let mut fine = Boot::new();
+            fine.set_smoothing(smoothing);
+            for _ in 0..100 {
+                fine.set_progress(0.8, 0.01);
+            }
+            assert!(
+                (coarse.smoothed_progress() - fine.smoothed_progress()).abs() < 1e-3,
+                "{:?}",
+                smoothing
+            );
+
+            // The smoothed progress never exceeds the actual progress, even with a huge step
+            assert!(coarse.smoothed_progress() <= coarse.progress());
+            coarse.set_progress(0.9, 1000.0);
+            assert_eq!(coarse.smoothed_progress(), 0.9);
+        }
+
+        let mut boot = Boot::new();
+        boot.set_smoothing(ProgressSmoothing::None);
+        boot.set_progress(0.3, 0.0);
+        assert_eq!(boot.smoothed_progress(), 0.3);
+
+        boot.set_smoothing(ProgressSmoothing::Linear { speed: 0.1 });
+        boot.set_progress(1.0, 1.0);
+        assert!((boot.smoothed_progress() - 0.4).abs() < 1e-6);
+
+        // The progress never goes backward
+        boot.set_progress(0.2, 1.0);
+        assert_eq!(boot.progress(), 1.0);
+        assert!((boot.smoothed_progress() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn unsubmitted() {
+        let mut app = App::new();
+        app.add_plugin(bevy::core::CorePlugin)
+            .add_plugin(bevy::asset::AssetPlugin)
+            .add_plugin(BootloaderPlugin);
+
+        // A boot whose loader has enqueued assets but was not submitted yet has no progress
+        let mut loader = Loader::new();
+        loader.enqueue("a.png");
+        loader.enqueue("b.png");
+        assert_eq!(upper_progress(&loader), 0.0);
+        assert_eq!(target_progress(&loader), 0.0);
+        let entity = app
+            .world
+            .spawn()
+            .insert_bundle(BootBundle::new(loader))
+            .id();
+        for _ in 0..3 {
+            app.update();
+        }
+        let boot = app.world.get::<Boot>(entity).unwrap();
+        assert_eq!(boot.progress(), 0.0);
+        assert_eq!(boot.smoothed_progress(), 0.0);
+    }
+
+    #[test]
+    fn cancel() {
+        let mut app = App::new();
+        app.add_plugin(bevy::core::CorePlugin)
+            .add_plugin(bevy::asset::AssetPlugin)
+            .add_plugin(BootloaderPlugin);
+        let screen = app.world.spawn().insert(BootScreen).id();
+
+        let mut loader = Loader::new();
+        loader.enqueue_task("forever", future::pending::<()>());
+        loader.submit();
+        let entity = app
+            .world
+            .spawn()
+            .insert_bundle(BootBundle::new(loader).with_id("level"))
+            .id();
+        app.update();
+        assert!(app.world.get::<Boot>(entity).is_some());
+
+        // A cancelled boot ends there, and is despawned with the shared boot screen
+        app.world.get_mut::<Loader>(entity).unwrap().cancel();
+        app.update();
+        assert!(app.world.get_entity(entity).is_none());
+        assert!(app.world.get_entity(screen).is_none());
+        let events = app.world.get_resource::<Events<BootCancelled>>().unwrap();
+        let cancelled: Vec<&BootCancelled> = events.get_reader().iter(events).collect();
+        assert_eq!(cancelled.len(), 1);
+        assert_eq!(cancelled[0].entity, entity);
+        assert_eq!(cancelled[0].id, Some(BootId::from("level")));
+        let events = app.world.get_resource::<Events<BootCompleted>>().unwrap();
+        assert_eq!(events.get_reader().iter(events).count(), 0);
+    }
+
+    #[test]
+    fn cancel_from_system() {
+        let mut app = App::new();
+        app.add_plugin(bevy::core::CorePlugin)
+            .add_plugin(bevy::asset::AssetPlugin)
+            .add_plugin(BootloaderPlugin);
+
+        // Cancel from a gameplay system running before update_boot in the same stage
+        fn cancel_system(mut query: Query<&mut Loader>) {
+            for mut loader in query.iter_mut() {
+                if loader.state() == crate::LoaderState::Loading {
+                    loader.cancel();
+                }
+            }
+        }
+        app.add_system(cancel_system.before(BootSystem::UpdateBoot));
+
+        let mut loader = Loader::new();
+        loader.enqueue_task("forever", future::pending::<()>());
+        loader.submit();
+        let entity = app
+            .world
+            .spawn()
+            .insert_bundle(BootBundle::new(loader))
+            .id();
+        app.update();
+
+        // The entity is despawned by update_boot, which still notifies the cancelled batch
+        assert!(app.world.get_entity(entity).is_none());
+        assert_eq!(drain_events::<BootCancelled>(&mut app.world).len(), 1);
+        let cancelled = drain_events::<LoaderCancelled>(&mut app.world);
+        assert_eq!(cancelled.len(), 1);
+        assert_eq!(cancelled[0].entity, entity);
+        assert_eq!(cancelled[0].paths, vec!["forever".to_string()]);
+        app.update();
+        assert!(drain_events::<LoaderCancelled>(&mut app.world).is_empty());
+    }
+
+    /// Drain all the events of the given type sent so far.
+    fn drain_events<T: Resource>(world: &mut World) -> Vec<T> {
+        let mut events = world.get_resource_mut::<Events<T>>().unwrap();
+        events.drain().collect()
+    }
+
+    #[test]
+    fn update() {
+        let (mut app, io) = test_app();
+        app.add_plugin(BootloaderPlugin);
+        io.add("a.test", "");
+        io.add("b.test", "");
+        io.set_blocked("b.test", true);
+        let shared_screen = app.world.spawn().insert(BootScreen).id();
+        let screen_a = app
+            .world
+            .spawn()
+            .insert(BootScreen)
+            .insert(BootId::from("a"))
+            .id();
+        let screen_b = app.world.spawn().id();
+
+        // Two independent boot sequences
+        let mut loader = Loader::new();
+        loader.enqueue("a.test");
+        loader.submit();
+        let boot_a = app
+            .world
+            .spawn()
+            .insert_bundle(BootBundle::new(loader).with_id("a"))
+            .id();
+        let mut loader = Loader::new();
+        loader.enqueue("b.test");
+        loader.submit();
+        let boot_b = app
+            .world
+            .spawn()
+            .insert_bundle(
+                BootBundle::new(loader)
+                    .with_id("b")
+                    .with_entities([screen_b]),
+            )
+            .id();
+
+        // The first boot completes, keeping its assets, and despawning only its own screen
+        update_until(&mut app, |world| world.get_entity(boot_a).is_none());
+        let completed = drain_events::<BootCompleted>(&mut app.world);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].entity, boot_a);
+        assert_eq!(completed[0].id, Some(BootId::from("a")));
+        assert_eq!(completed[0].asset_count, 1);
+        assert!(completed[0].handles.contains_key("a.test"));
+        let boot_assets = app.world.get_resource::<BootAssets>().unwrap();
+        assert!(boot_assets.contains("a.test"));
+        let assets = app.world.get_resource::<Assets<TestAsset>>().unwrap();
+        assert!(boot_assets.get_typed("a.test", assets).is_ok());
+        assert!(!boot_assets.contains("b.test"));
+        assert!(app.world.get_entity(screen_a).is_none());
+        assert!(app.world.get_entity(screen_b).is_some());
+        assert!(app.world.get_entity(shared_screen).is_some());
+        let boot = app.world.get::<Boot>(boot_b).unwrap();
+        assert!(!boot.is_completed());
+
+        // The shared screen is despawned once the last boot completes
+        io.set_blocked("b.test", false);
+        update_until(&mut app, |world| world.get_entity(boot_b).is_none());
+        let completed = drain_events::<BootCompleted>(&mut app.world);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].id, Some(BootId::from("b")));
+        assert!(app.world.get_entity(screen_b).is_none());
+        assert!(app.world.get_entity(shared_screen).is_none());
+        let boot_assets = app.world.get_resource::<BootAssets>().unwrap();
+        assert!(boot_assets.contains("a.test"));
+        assert!(boot_assets.contains("b.test"));
+        assert!(drain_events::<BootFailed>(&mut app.world).is_empty());
+    }
+
+    #[test]
+    fn asset_count() {
+        let (mut app, io) = test_app();
+        app.add_plugin(BootloaderPlugin);
+        io.add("a.test", "");
+        io.add("b.test", "");
+        io.set_blocked("b.test", true);
+
+        // Only the assets which loaded count, not the tasks nor the failed optional assets
+        let mut loader = Loader::new();
+        loader.enqueue("a.test");
+        loader.enqueue("b.test");
+        loader.enqueue_optional("missing.test");
+        loader.enqueue_task("task", async {});
+        loader.submit();
+        let entity = app
+            .world
+            .spawn()
+            .insert_bundle(BootBundle::new(loader))
+            .id();
+        update_until(&mut app, |world| {
+            let loader = world.get::<Loader>(entity).unwrap();
+            loader.pending_count() == 1
+        });
+        // An asset taken before completion still counts
+        let mut loader = app.world.get_mut::<Loader>(entity).unwrap();
+        assert!(loader.take("a.test").is_ok());
+        io.set_blocked("b.test", false);
+        update_until(&mut app, |world| world.get_entity(entity).is_none());
+        let completed = drain_events::<BootCompleted>(&mut app.world);
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].asset_count, 2);
+        assert_eq!(completed[0].handles.len(), 1);
+    }
+
+    #[test]
+    fn without_boot_assets() {
+        let (mut app, io) = test_app();
+        app.add_plugin(crate::LoaderPlugin)
+            .add_event::<BootCompleted>()
+            .add_event::<BootFailed>()
+            .add_event::<BootCancelled>()
+            .add_system(update_boot);
+        io.add("a.test", "");
+
+        // Without the BootAssets resource, the handles are only given to the event
+        let mut loader = Loader::new();
+        loader.enqueue("a.test");
+        loader.submit();
+        let entity = app
+            .world
+            .spawn()
+            .insert_bundle(BootBundle::new(loader))
+            .id();
+        update_until(&mut app, |world| world.get_entity(entity).is_none());
+        assert!(app.world.get_resource::<BootAssets>().is_none());
+        let completed = drain_events::<BootCompleted>(&mut app.world);
+        assert_eq!(completed.len(), 1);
+        assert!(completed[0].handles.contains_key("a.test"));
+    }
+
+    #[test]
+    fn failure() {
+        let (mut app, io) = test_app();
+        app.add_plugin(BootloaderPlugin);
+        io.add("a.test", "");
+        let shared_screen = app.world.spawn().insert(BootScreen).id();
+
+        let mut loader = Loader::new();
+        loader.enqueue("a.test");
+        loader.enqueue("missing.test");
+        loader.submit();
+        let entity = app
+            .world
+            .spawn()
+            .insert_bundle(BootBundle::new(loader))
+            .id();
+
+        // A failed boot sends an event once, and isn't despawned
+        update_until(&mut app, |world| {
+            world.get::<Boot>(entity).unwrap().is_failed()
+        });
+        let failed = drain_events::<BootFailed>(&mut app.world);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].entity, entity);
+        assert_eq!(failed[0].id, None);
+        assert_eq!(failed[0].failed_paths, vec!["missing.test".to_string()]);
+        assert_eq!(failed[0].phase, None);
+        for _ in 0..3 {
+            app.update();
         }
+        assert!(drain_events::<BootFailed>(&mut app.world).is_empty());
+        assert!(drain_events::<BootCompleted>(&mut app.world).is_empty());
+        assert!(app.world.get_entity(entity).is_some());
+        assert!(app.world.get_entity(shared_screen).is_some());
+        assert!(app
+            .world
+            .get_resource::<BootAssets>()
+            .unwrap()
+            .iter()
+            .next()
+            .is_none());
     }
 }
@@ -0,0 +1,278 @@
+use bevy::{asset::Asset, ecs::system::Resource, prelude::*};
+use std::marker::PhantomData;
+
+use crate::loader::{Loader, LoaderError, TakeError, TypeMismatch};
+
+/// Collection of assets loaded together by a [`Loader`], and made available as a resource.
+///
+/// This trait is generally not implemented manually, but derived with
+/// `#[derive(AssetCollection)]` (requires the `derive` feature, enabled by default). Each field
+/// of the struct is an asset handle annotated with the path of the asset to load:
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_bootloader::*;
+/// #[derive(AssetCollection)]
+/// struct UiAssets {
+///     #[asset(path = "ui/atlas.png")]
+///     atlas: Handle<Image>,
+///     #[asset(path = "ui/cursor.png")]
+///     cursor: Handle<Image>,
+///     // Optional assets don't fail the batch if they fail to load
+///     #[asset(path = "ui/splash.png", optional)]
+///     splash: Option<Handle<Image>>,
+/// }
+/// ```
+///
+/// Add an [`AssetCollectionPlugin`] to load the collection at startup and insert it as a
+/// resource once loaded.
+pub trait AssetCollection: Resource + Sized {
+    /// Enqueue all the assets of the collection into the given loader.
+    fn enqueue(loader: &mut Loader) -> Result<(), LoaderError>;
+
+    /// Create the collection from the assets loaded by the given loader, taking the handles
+    /// out of it.
+    ///
+    /// The world gives access to the [`Assets`] storages, to check the type of each asset.
+    /// If any required asset cannot be taken, an error is returned and the loader is left
+    /// untouched.
+    fn create(loader: &mut Loader, world: &World) -> Result<Self, TakeError>;
+}
+
+/// Get the [`Assets`] storage of the asset type `T`, for the derived [`AssetCollection`].
+///
+/// If the storage doesn't exist, no asset can be of type `T`, and this returns a
+/// [`TakeError::TypeMismatch`].
+#[doc(hidden)]
+pub fn collection_assets<T: Asset>(world: &World) -> Result<&Assets<T>, TakeError> {
+    world
+        .get_resource::<Assets<T>>()
+        .ok_or_else(|| TakeError::TypeMismatch(TypeMismatch::of::<T>()))
+}
+
+/// Marker component for the entity holding the [`Loader`] of an [`AssetCollection`].
+#[derive(Component)]
+struct CollectionLoader<T: AssetCollection>(PhantomData<T>);
+
+/// Plugin to load an [`AssetCollection`] at startup, and insert it as a resource once loaded.
+///
+/// The plugin spawns at startup an entity holding a [`Loader`] with all the assets of the
+/// collection. Once the loader is done, the collection is created from the loaded assets and
+/// inserted as a resource, and the entity is despawned. If the loader batch fails, an error
+/// is logged and the entity is left in place to allow inspecting the [`Loader`].
+///
+/// This plugin relies on the [`LoaderPlugin`] to update the [`Loader`], which must be added
+/// separately, generally via the [`BootloaderPlugin`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_bootloader::*;
+/// #[derive(AssetCollection)]
+/// struct UiAssets {
+///     #[asset(path = "ui/atlas.png")]
+///     atlas: Handle<Image>,
+/// }
+///
+/// fn use_assets(ui_assets: Option<Res<UiAssets>>) {
+///     if let Some(ui_assets) = ui_assets {
+///         // ...
+///     }
+/// }
+///
+/// App::default()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugin(BootloaderPlugin)
+///     .add_plugin(AssetCollectionPlugin::<UiAssets>::default())
+///     .add_system(use_assets)
+///     .run();
+/// ```
+///
+/// [`LoaderPlugin`]: crate::LoaderPlugin
+/// [`BootloaderPlugin`]: crate::BootloaderPlugin
+#[derive(Debug, Clone, Copy)]
+pub struct AssetCollectionPlugin<T: AssetCollection>(PhantomData<T>);
+
+impl<T: AssetCollection> Default for AssetCollectionPlugin<T> {
+    fn default() -> Self {
+        AssetCollectionPlugin(PhantomData)
+    }
+}
+
+impl<T: AssetCollection> Plugin for AssetCollectionPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(start_collection::<T>)
+            .add_system(finish_collection::<T>.exclusive_system());
+    }
+}
+
+fn start_collection<T: AssetCollection>(mut commands: Commands) {
+    let mut loader = Loader::new();
+    if let Err(err) = T::enqueue(&mut loader) {
+        error!(
+            "Failed to enqueue asset collection {}: {}",
+            std::any::type_name::<T>(),
+            err
+        );
+        return;
+    }
+    loader.submit();
+    commands
+        .spawn()
+        .insert(loader)
+        .insert(CollectionLoader::<T>(PhantomData));
+}
+
+fn finish_collection<T: AssetCollection>(world: &mut World) {
+    let mut done = vec![];
+    let mut failed = vec![];
+    let mut query = world.query_filtered::<(Entity, &Loader), With<CollectionLoader<T>>>();
+    for (entity, loader) in query.iter(world) {
+        if loader.is_done() {
+            done.push(entity);
+        } else if loader.is_batch_failed() {
+            error!(
+                "Failed to load asset collection {}: {:?}",
+                std::any::type_name::<T>(),
+                loader.failed_paths()
+            );
+            failed.push(entity);
+        }
+    }
+    for entity in failed {
+        world.entity_mut(entity).remove::<CollectionLoader<T>>();
+    }
+    for entity in done {
+        // Take the loader out of its entity, to access the assets from the world
+        let mut loader = world.entity_mut(entity).remove::<Loader>().unwrap();
+        match T::create(&mut loader, world) {
+            Ok(collection) => world.insert_resource(collection),
+            Err(err) => error!(
+                "Failed to create asset collection {}: {}",
+                std::any::type_name::<T>(),
+                err
+            ),
+        }
+        world.despawn(entity);
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{test_app, update_until, TestAsset},
+        AssetCollection, LoaderPlugin,
+    };
+
+    #[derive(AssetCollection)]
+    struct TestAssets {
+        #[asset(path = "a.test")]
+        a: Handle<TestAsset>,
+        #[asset(path = "b.test")]
+        b: Handle<TestAsset>,
+        #[asset(path = "c.test", optional)]
+        c: Option<Handle<TestAsset>>,
+    }
+
+    #[derive(AssetCollection)]
+    struct DuplicateAssets {
+        #[asset(path = "a.test")]
+        _a: Handle<TestAsset>,
+        #[asset(path = "a.test")]
+        _b: Handle<TestAsset>,
+    }
+
+    #[derive(AssetCollection)]
+    struct MissingAssets {
+        #[asset(path = "a.test")]
+        _a: Handle<TestAsset>,
+        #[asset(path = "d.test")]
+        _d: Handle<TestAsset>,
+    }
+
+    #[derive(AssetCollection)]
+    struct MismatchAssets {
+        #[asset(path = "a.test")]
+        _a: Handle<TestAsset>,
+        #[asset(path = "b.test")]
+        _b: Handle<Image>,
+    }
+
+    #[test]
+    fn derive() {
+        let mut loader = Loader::new();
+        assert_eq!(
+            DuplicateAssets::enqueue(&mut loader),
+            Err(LoaderError::DuplicatePath("a.test".to_string()))
+        );
+
+        let (mut app, io) = test_app();
+        app.add_plugin(LoaderPlugin);
+        io.add("a.test", "");
+        io.add("b.test", "");
+
+        let mut loader = Loader::new();
+        assert_eq!(TestAssets::enqueue(&mut loader), Ok(()));
+        assert_eq!(loader.pending_count(), 3);
+        assert!(!loader.is_optional("a.test"));
+        assert!(loader.is_optional("c.test"));
+        loader.submit();
+        let entity = app.world.spawn().insert(loader).id();
+        update_until(&mut app, |world| {
+            world.get::<Loader>(entity).unwrap().is_done()
+        });
+        let mut loader = app.world.entity_mut(entity).remove::<Loader>().unwrap();
+
+        // A missing or mistyped required asset leaves the loader untouched
+        assert_eq!(
+            MissingAssets::create(&mut loader, &app.world).err(),
+            Some(TakeError::NotFound)
+        );
+        assert_eq!(
+            MismatchAssets::create(&mut loader, &app.world).err(),
+            Some(TakeError::TypeMismatch(TypeMismatch::of::<Image>()))
+        );
+
+        let assets = TestAssets::create(&mut loader, &app.world).unwrap();
+        let storage = app.world.get_resource::<Assets<TestAsset>>().unwrap();
+        assert!(storage.contains(&assets.a));
+        assert!(storage.contains(&assets.b));
+        assert!(assets.c.is_none());
+        assert_eq!(loader.take("a.test"), Err(TakeError::NotFound));
+        assert_eq!(loader.take("b.test"), Err(TakeError::NotFound));
+    }
+
+    #[test]
+    fn plugin() {
+        let (mut app, io) = test_app();
+        app.add_plugin(LoaderPlugin)
+            .add_plugin(AssetCollectionPlugin::<TestAssets>::default());
+        io.add("a.test", "");
+        io.add("b.test", "");
+        update_until(&mut app, |world| {
+            world.get_resource::<TestAssets>().is_some()
+        });
+        let mut query = app.world.query::<&Loader>();
+        assert_eq!(query.iter(&app.world).count(), 0);
+
+        // A failed batch keeps its loader around for inspection
+        let (mut app, io) = test_app();
+        app.add_plugin(LoaderPlugin)
+            .add_plugin(AssetCollectionPlugin::<MissingAssets>::default());
+        io.add("a.test", "");
+        update_until(&mut app, |world| {
+            let mut query = world.query::<&Loader>();
+            query.iter(world).any(|loader| loader.is_batch_failed())
+        });
+        app.update();
+        assert!(app.world.get_resource::<MissingAssets>().is_none());
+        let mut query = app
+            .world
+            .query::<(&Loader, Option<&CollectionLoader<MissingAssets>>)>();
+        let (loader, marker) = query.iter(&app.world).next().unwrap();
+        assert_eq!(loader.failed_paths(), vec!["d.test".to_string()]);
+        assert!(marker.is_none());
+    }
+}
@@ -0,0 +1,149 @@
+use bevy::{
+    asset::{Asset, HandleId},
+    prelude::*,
+};
+use std::sync::Arc;
+
+use crate::loader::{Loader, LoaderStage, LoaderSystem};
+
+/// Function returning the dependencies of an asset of type `T`.
+type ResolverFn<T> = Arc<dyn Fn(&T) -> Vec<HandleId> + Send + Sync>;
+
+/// Resource holding the function returning the dependencies of an asset of type `T`.
+struct DependencyResolver<T: Asset>(ResolverFn<T>);
+
+/// Plugin to make all [`Loader`] instances wait for the dependencies of assets of type `T`.
+///
+/// Some composite assets, like glTF scenes, report being loaded while the other assets they
+/// depend on, like textures, are still loading. The asset server doesn't expose those
+/// dependencies, so this plugin relies on a resolver function to list them from the loaded
+/// asset. Once an asset of type `T` loaded, the [`Loader`] calls the resolver, then waits for
+/// all the returned dependencies to load, using [`AssetServer::get_group_load_state()`], before
+/// considering the asset itself as loaded. If any dependency fails to load, the asset is marked
+/// as failed.
+///
+/// Only direct dependencies are waited for; the resolver can return nested dependencies if
+/// needed, provided they're already known. Dependencies are not counted in the progress by
+/// default; see [`Loader::set_count_dependencies()`].
+///
+/// This plugin must be added after the [`LoaderPlugin`], generally added via the
+/// [`BootloaderPlugin`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::{asset::HandleId, prelude::*, reflect::TypeUuid};
+/// # use bevy_bootloader::*;
+/// #[derive(TypeUuid)]
+/// #[uuid = "7b6c0c36-4c43-4d48-9ad6-5e2f8a3e3a43"]
+/// struct Level {
+///     textures: Vec<Handle<Image>>,
+/// }
+///
+/// App::default()
+///     .add_plugins(DefaultPlugins)
+///     .add_asset::<Level>()
+///     .add_plugin(BootloaderPlugin)
+///     .add_plugin(AssetDependenciesPlugin::<Level>::new(|level: &Level| {
+///         level.textures.iter().map(|handle| handle.id).collect::<Vec<HandleId>>()
+///     }))
+///     .run();
+/// ```
+///
+/// [`LoaderPlugin`]: crate::LoaderPlugin
+/// [`BootloaderPlugin`]: crate::BootloaderPlugin
+pub struct AssetDependenciesPlugin<T: Asset> {
+    /// Function returning the dependencies of an asset.
+    resolver: ResolverFn<T>,
+}
+
+impl<T: Asset> AssetDependenciesPlugin<T> {
+    /// Create a plugin using the given function to list the dependencies of an asset.
+    pub fn new(resolver: impl Fn(&T) -> Vec<HandleId> + Send + Sync + 'static) -> Self {
+        AssetDependenciesPlugin {
+            resolver: Arc::new(resolver),
+        }
+    }
+}
+
+impl<T: Asset> std::fmt::Debug for AssetDependenciesPlugin<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AssetDependenciesPlugin")
+            .field("asset", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+impl<T: Asset> Plugin for AssetDependenciesPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(DependencyResolver(Arc::clone(&self.resolver)))
+            .add_system_to_stage(
+                LoaderStage::UpdateLoaders,
+                resolve_dependencies::<T>.before(LoaderSystem::TickLoaders),
+            );
+    }
+}
+
+fn resolve_dependencies<T: Asset>(
+    asset_server: Res<AssetServer>,
+    resolver: Res<DependencyResolver<T>>,
+    assets: Res<Assets<T>>,
+    mut query: Query<&mut Loader>,
+) {
+    for mut loader in query.iter_mut() {
+        loader.resolve_dependencies(&asset_server, |id| {
+            assets.get(id).map(|asset| (resolver.0)(asset))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{test_app, update_until, TestAsset},
+        LoaderPlugin, LoaderState,
+    };
+
+    #[test]
+    fn dependencies() {
+        let (mut app, io) = test_app();
+        app.add_plugin(LoaderPlugin)
+            .add_plugin(AssetDependenciesPlugin::<TestAsset>::new(|asset| {
+                asset.dependencies.iter().map(|handle| handle.id).collect()
+            }));
+        io.add("level.test", "a.test\nb.test\n");
+        io.add("a.test", "");
+        io.add("b.test", "");
+        io.set_blocked("b.test", true);
+
+        let mut loader = Loader::new();
+        loader.set_count_dependencies(true);
+        loader.enqueue("level.test");
+        loader.submit();
+        let entity = app.world.spawn().insert(loader).id();
+
+        // The asset itself and one dependency loaded; the batch waits for the other one
+        update_until(&mut app, |world| {
+            world.get::<Loader>(entity).unwrap().progress() > 0.0
+        });
+        for _ in 0..3 {
+            app.update();
+        }
+        let loader = app.world.get::<Loader>(entity).unwrap();
+        assert_eq!(loader.state(), LoaderState::Loading);
+        assert_eq!(loader.pending_count(), 1);
+        assert_eq!(loader.total_weight(), 1.0 + 2.0);
+        assert!((loader.pending_weight() - 2.0).abs() < 1e-6);
+        assert!((loader.progress() - 1.0 / 3.0).abs() < 1e-6);
+
+        io.set_blocked("b.test", false);
+        update_until(&mut app, |world| {
+            world.get::<Loader>(entity).unwrap().is_done()
+        });
+        let mut loader = app.world.get_mut::<Loader>(entity).unwrap();
+        assert_eq!(loader.total_weight(), 1.0 + 2.0);
+        assert_eq!(loader.progress(), 1.0);
+        assert!(loader.take("level.test").is_ok());
+    }
+}
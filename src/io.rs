@@ -0,0 +1,187 @@
+use bevy::asset::{AssetIo, AssetIoError, BoxedFuture};
+use parking_lot::RwLock;
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Number of bytes read from an [`AssetIo`], per asset path.
+///
+/// This is shared between a [`ProgressAssetIo`] recording the bytes it reads, and the
+/// [`Loader`] instances reporting their byte-based progress. Insert a copy obtained with
+/// [`ProgressAssetIo::stats()`] as a resource to enable [`Loader::progress_bytes()`].
+///
+/// [`Loader`]: crate::Loader
+/// [`Loader::progress_bytes()`]: crate::Loader::progress_bytes
+#[derive(Debug, Clone, Default)]
+pub struct AssetIoStats(Arc<RwLock<HashMap<PathBuf, u64>>>);
+
+impl AssetIoStats {
+    /// Number of bytes read for the asset at the given path, or `None` if it was not read yet.
+    pub fn bytes_read(&self, path: &Path) -> Option<u64> {
+        self.0.read().get(path).copied()
+    }
+
+    /// Total number of bytes read for all assets.
+    pub fn total_bytes_read(&self) -> u64 {
+        self.0.read().values().sum()
+    }
+
+    /// Record the number of bytes read for the asset at the given path.
+    fn record(&self, path: &Path, bytes: u64) {
+        self.0.write().insert(path.to_path_buf(), bytes);
+    }
+}
+
+/// Wrapper around an [`AssetIo`] counting the bytes read for each asset path.
+///
+/// The wrapper forwards all operations to the wrapped [`AssetIo`], and records into its
+/// [`AssetIoStats`] the size of each asset file once it was entirely read; partial reads are not
+/// reported. Insert a copy of those stats as a resource to let the [`Loader`] instances report
+/// their progress in bytes with [`Loader::progress_bytes()`]. That progress still advances file
+/// by file, but weights each file by its size, which is more accurate than counting assets when
+/// a few asset files are much larger than the others.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::{asset::FileAssetIo, prelude::*, tasks::IoTaskPool};
+/// # use bevy_bootloader::*;
+/// # let mut app = App::new();
+/// let task_pool = app.world.get_resource::<IoTaskPool>().unwrap().0.clone();
+/// let asset_io = ProgressAssetIo::new(FileAssetIo::new("assets"));
+/// app.insert_resource(asset_io.stats())
+///     .insert_resource(AssetServer::with_boxed_io(Box::new(asset_io), task_pool));
+/// ```
+///
+/// [`Loader`]: crate::Loader
+/// [`Loader::progress_bytes()`]: crate::Loader::progress_bytes
+pub struct ProgressAssetIo {
+    /// Wrapped asset I/O reading the actual asset files.
+    inner: Box<dyn AssetIo>,
+    /// Number of bytes read per asset path.
+    stats: AssetIoStats,
+}
+
+impl ProgressAssetIo {
+    /// Create a wrapper around the given [`AssetIo`].
+    pub fn new(inner: impl AssetIo) -> Self {
+        Self::from_boxed(Box::new(inner))
+    }
+
+    /// Create a wrapper around the given boxed [`AssetIo`].
+    pub fn from_boxed(inner: Box<dyn AssetIo>) -> Self {
+        ProgressAssetIo {
+            inner,
+            stats: AssetIoStats::default(),
+        }
+    }
+
+    /// Get the stats recording the bytes read by this wrapper.
+    ///
+    /// The returned value shares its data with the wrapper, and observes all reads made after
+    /// the wrapper is handed over to the [`AssetServer`].
+    ///
+    /// [`AssetServer`]: bevy::asset::AssetServer
+    pub fn stats(&self) -> AssetIoStats {
+        self.stats.clone()
+    }
+}
+
+impl fmt::Debug for ProgressAssetIo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressAssetIo")
+            .field("stats", &self.stats)
+            .finish()
+    }
+}
+
+impl AssetIo for ProgressAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            let bytes = self.inner.load_path(path).await?;
+            self.stats.record(path, bytes.len() as u64);
+            Ok(bytes)
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        self.inner.read_directory(path)
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.inner.is_directory(path)
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        self.inner.watch_path_for_changes(path)
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        self.inner.watch_for_changes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_lite::future;
+
+    struct MemoryAssetIo;
+
+    impl AssetIo for MemoryAssetIo {
+        fn load_path<'a>(
+            &'a self,
+            path: &'a Path,
+        ) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+            Box::pin(async move {
+                match path.to_str() {
+                    Some("small.bin") => Ok(vec![0; 16]),
+                    Some("large.bin") => Ok(vec![0; 1024]),
+                    _ => Err(AssetIoError::NotFound(path.to_path_buf())),
+                }
+            })
+        }
+
+        fn read_directory(
+            &self,
+            path: &Path,
+        ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+            Err(AssetIoError::NotFound(path.to_path_buf()))
+        }
+
+        fn is_directory(&self, _path: &Path) -> bool {
+            false
+        }
+
+        fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+            Ok(())
+        }
+
+        fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let asset_io = ProgressAssetIo::new(MemoryAssetIo);
+        let stats = asset_io.stats();
+        assert_eq!(stats.total_bytes_read(), 0);
+
+        let bytes = future::block_on(asset_io.load_path(Path::new("small.bin"))).unwrap();
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(stats.bytes_read(Path::new("small.bin")), Some(16));
+        assert_eq!(stats.bytes_read(Path::new("large.bin")), None);
+
+        future::block_on(asset_io.load_path(Path::new("large.bin"))).unwrap();
+        assert!(future::block_on(asset_io.load_path(Path::new("missing.bin"))).is_err());
+        assert_eq!(stats.bytes_read(Path::new("missing.bin")), None);
+        assert_eq!(stats.total_bytes_read(), 16 + 1024);
+    }
+}
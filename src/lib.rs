@@ -36,6 +36,9 @@
 //!     .run();
 //! ```
 //!
+//! Alternatively, use [`BootloaderPlugin::with_states()`] to run the boot sequence only in a given
+//! app state, and automatically transition to another state once boot completes.
+//!
 //! Queue boot-time critical assets, and insert a [`BootBundle`]:
 //!
 //! ```
@@ -82,13 +85,76 @@
 //! }
 //! ```
 //!
-//! See the `bootloader` example for the full code.
+//! Alternatively, add the [`DefaultBootScreenPlugin`] to get such a progress bar out of the box,
+//! styled with the [`BootScreenStyle`] resource:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_bootloader::*;
+//! App::default()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugin(BootloaderPlugin)
+//!     .add_plugin(DefaultBootScreenPlugin)
+//!     .run();
+//! ```
+//!
+//! See the `bootloader` example for a full app using the [`DefaultBootScreenPlugin`].
+//!
+//! # Asset collections
+//!
+//! With the `derive` feature (enabled by default), a struct of asset handles can derive
+//! [`AssetCollection`] to be loaded by a [`Loader`] and inserted as a resource once loaded,
+//! via the [`AssetCollectionPlugin`]:
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_bootloader::*;
+//! #[derive(AssetCollection)]
+//! struct MenuAssets {
+//!     #[asset(path = "menu/background.png")]
+//!     background: Handle<Image>,
+//!     #[asset(path = "menu/logo.png")]
+//!     logo: Handle<Image>,
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_plugin(AssetCollectionPlugin::<MenuAssets>::default());
+//! ```
 //!
 
 mod boot;
+mod collection;
+mod dependencies;
+mod io;
 mod loader;
 mod plugin;
+mod screen;
+mod sequence;
+#[cfg(test)]
+mod testing;
+mod timings;
+
+pub use boot::{
+    update_boot, Boot, BootAssets, BootBundle, BootCancelled, BootCompleted, BootFailed, BootId,
+    BootScreen, BootSystem, ProgressSmoothing,
+};
+#[doc(hidden)]
+pub use collection::collection_assets;
+pub use collection::{AssetCollection, AssetCollectionPlugin};
+pub use dependencies::AssetDependenciesPlugin;
+pub use io::{AssetIoStats, ProgressAssetIo};
+pub use loader::{
+    Backoff, FailureReason, Loader, LoaderCancelled, LoaderError, LoaderPlugin, LoaderStage,
+    LoaderState, LoaderSystem, LoaderTimedOut, RetryDelay, RetryPolicy, TakeError, TypeMismatch,
+};
+pub use plugin::{BootloaderPlugin, BootloaderStatePlugin};
+pub use screen::{BootScreenStyle, DefaultBootScreenPlugin};
+pub use sequence::{BootPhase, BootSequence, PhaseStatus};
+pub use timings::{LoadTimings, LoadTimingsError, LoadTimingsPlugin};
+
+#[cfg(feature = "derive")]
+pub use bevy_bootloader_derive::AssetCollection;
 
-pub use boot::{update_boot, Boot, BootBundle};
-pub use loader::{Loader, LoaderPlugin, LoaderStage};
-pub use plugin::BootloaderPlugin;
+// Allow the derived code to refer to this crate by name in its own tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as bevy_bootloader;
@@ -1,12 +1,29 @@
-use bevy::{asset::AssetStage, prelude::*};
+use bevy::{
+    asset::{Asset, AssetPath, AssetServerSettings, AssetStage, HandleId, LoadState},
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, IoTaskPool, Task, TaskPool},
+    utils::{Duration, Instant, Uuid},
+};
+use futures_lite::future;
+
+use crate::{io::AssetIoStats, timings::LoadTimings};
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+use bevy::asset::FileAssetIo;
 use parking_lot::{Mutex, RwLock};
 use std::{
-    collections::HashMap,
+    any::Any,
+    collections::{HashMap, HashSet},
+    future::Future,
+    path::{Path, PathBuf},
+    pin::Pin,
     sync::atomic::{AtomicUsize, Ordering},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum State {
+/// Lifecycle state of a [`Loader`].
+///
+/// See the [`Loader`] documentation for details on the lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoaderState {
     /// Idle state where a [`Loader`] is ready to receive new requests.
     Ready,
     /// Loading state during which the [`Loader`] actively work with the asset server
@@ -14,6 +31,424 @@ pub enum State {
     Loading,
     /// Final state indicating the group of assets has been loaded.
     Done,
+    /// Final state indicating the group of assets finished loading, but at least one
+    /// required asset failed to load.
+    Failed,
+    /// Final state indicating the batch was cancelled with [`Loader::cancel()`] before all
+    /// assets finished loading.
+    Cancelled,
+}
+
+/// Reason why an asset failed to load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureReason {
+    /// The asset server reported the asset as [`LoadState::Failed`], for example because
+    /// the file was not found or the asset loader returned an error.
+    ///
+    /// [`LoadState::Failed`]: bevy::asset::LoadState::Failed
+    LoadFailed,
+    /// The asset server reported the asset as [`LoadState::Unloaded`] before it finished loading.
+    ///
+    /// [`LoadState::Unloaded`]: bevy::asset::LoadState::Unloaded
+    Unloaded,
+    /// The asset did not finish loading before its deadline. See [`Loader::set_asset_timeout()`]
+    /// and [`Loader::set_batch_timeout()`].
+    TimedOut,
+}
+
+impl std::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FailureReason::LoadFailed => write!(f, "asset failed to load"),
+            FailureReason::Unloaded => write!(f, "asset was unloaded before it finished loading"),
+            FailureReason::TimedOut => write!(f, "asset timed out"),
+        }
+    }
+}
+
+/// Error indicating that a loaded asset is not of the type requested by the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeMismatch {
+    /// The [`TypeUuid`] of the requested asset type.
+    ///
+    /// [`TypeUuid`]: bevy::reflect::TypeUuid
+    pub expected_uuid: Uuid,
+    /// The name of the requested asset type.
+    pub expected_name: &'static str,
+}
+
+impl TypeMismatch {
+    pub(crate) fn of<T: Asset>() -> Self {
+        TypeMismatch {
+            expected_uuid: T::TYPE_UUID,
+            expected_name: std::any::type_name::<T>(),
+        }
+    }
+}
+
+impl std::fmt::Display for TypeMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "asset is not of type {} ({})",
+            self.expected_name, self.expected_uuid
+        )
+    }
+}
+
+impl std::error::Error for TypeMismatch {}
+
+/// Error returned when trying to take an asset out of a [`Loader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TakeError {
+    /// The path is unknown to the loader. Either it was never enqueued, or the asset was
+    /// already taken.
+    NotFound,
+    /// The asset is still pending, and did not finish loading yet.
+    Pending,
+    /// The asset failed to load.
+    Failed(FailureReason),
+    /// The asset loaded, but is not of the requested type.
+    TypeMismatch(TypeMismatch),
+    /// The task completed, but its output is not of the requested type, whose name is given.
+    TaskOutputMismatch(&'static str),
+}
+
+impl std::fmt::Display for TakeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TakeError::NotFound => write!(f, "unknown asset path"),
+            TakeError::Pending => write!(f, "asset is still loading"),
+            TakeError::Failed(reason) => write!(f, "{}", reason),
+            TakeError::TypeMismatch(mismatch) => write!(f, "{}", mismatch),
+            TakeError::TaskOutputMismatch(expected) => {
+                write!(f, "task output is not of type {}", expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TakeError {}
+
+/// Error returned by the fallible [`Loader`] operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoaderError {
+    /// The batch was already submitted, and the loader is still loading it.
+    AlreadySubmitted,
+    /// The loader is not ready to receive new requests, because a batch already completed.
+    /// Call [`Loader::reset()`] first.
+    NotReady,
+    /// The asset path or task name is already part of the batch.
+    DuplicatePath(String),
+    /// The loader is not loading a batch, so there's nothing to cancel.
+    NotLoading,
+}
+
+impl std::fmt::Display for LoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoaderError::AlreadySubmitted => write!(f, "loader batch already submitted"),
+            LoaderError::NotReady => write!(f, "loader not ready; reset it first"),
+            LoaderError::DuplicatePath(path) => write!(f, "duplicate asset path: {}", path),
+            LoaderError::NotLoading => write!(f, "loader batch not loading"),
+        }
+    }
+}
+
+impl std::error::Error for LoaderError {}
+
+/// Delay to wait for before retrying to load an asset which failed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetryDelay {
+    /// Delay expressed as a number of frames.
+    Frames(u32),
+    /// Delay expressed in seconds.
+    ///
+    /// The delay is clamped to at most one hour; a negative or NaN delay retries immediately.
+    Seconds(f32),
+}
+
+/// Maximum delay before retrying to load an asset, in seconds.
+const MAX_RETRY_DELAY_SECONDS: f32 = 3600.0;
+
+impl RetryDelay {
+    /// Scale the delay by the given factor.
+    fn scaled(&self, factor: f32) -> RetryDelay {
+        match *self {
+            RetryDelay::Frames(frames) => {
+                RetryDelay::Frames((frames as f32 * factor).round() as u32)
+            }
+            RetryDelay::Seconds(seconds) => RetryDelay::Seconds(seconds * factor),
+        }
+    }
+}
+
+/// Backoff strategy defining the delay between two consecutive attempts to load a same asset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backoff {
+    /// Wait for the same fixed delay before each retry.
+    Fixed(RetryDelay),
+    /// Wait for an exponentially increasing delay before each retry. The first retry
+    /// waits for the `initial` delay, and each subsequent retry waits `factor` times
+    /// longer than the previous one.
+    Exponential {
+        /// Delay before the first retry.
+        initial: RetryDelay,
+        /// Multiplying factor applied to the delay after each retry.
+        factor: f32,
+    },
+}
+
+/// Policy defining how a [`Loader`] retries loading assets which failed.
+///
+/// The default policy doesn't retry; any asset failing to load is immediately marked as failed.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_bootloader::*;
+/// let mut loader = Loader::new();
+/// // Try to load each asset up to 5 times, waiting 0.5 second after the first failure,
+/// // then 1 second, 2 seconds, and 4 seconds.
+/// loader.set_retry_policy(RetryPolicy::exponential(5, RetryDelay::Seconds(0.5), 2.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts to load a single asset, including the initial one.
+    /// A value of `1` (or `0`) disables retrying.
+    pub max_attempts: u32,
+    /// Backoff strategy defining the delay before each retry.
+    pub backoff: Backoff,
+    /// Maximum time to wait for the asset server to start a retried attempt, before trusting a
+    /// failed load state which may otherwise be the stale state of the previous attempt.
+    /// Defaults to one second.
+    pub start_timeout: Duration,
+}
+
+/// Default maximum time to wait for the asset server to start a retried attempt.
+const DEFAULT_RETRY_START_TIMEOUT: Duration = Duration::from_secs(1);
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::none()
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy which never retries.
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            backoff: Backoff::Fixed(RetryDelay::Frames(0)),
+            start_timeout: DEFAULT_RETRY_START_TIMEOUT,
+        }
+    }
+
+    /// Create a policy attempting up to `max_attempts` times to load an asset, with a fixed
+    /// `delay` between attempts.
+    pub fn fixed(max_attempts: u32, delay: RetryDelay) -> Self {
+        RetryPolicy {
+            max_attempts,
+            backoff: Backoff::Fixed(delay),
+            start_timeout: DEFAULT_RETRY_START_TIMEOUT,
+        }
+    }
+
+    /// Create a policy attempting up to `max_attempts` times to load an asset, with an
+    /// exponentially increasing delay between attempts, starting at `initial` and multiplied
+    /// by `factor` after each retry.
+    pub fn exponential(max_attempts: u32, initial: RetryDelay, factor: f32) -> Self {
+        RetryPolicy {
+            max_attempts,
+            backoff: Backoff::Exponential { initial, factor },
+            start_timeout: DEFAULT_RETRY_START_TIMEOUT,
+        }
+    }
+
+    /// Get the delay to wait for before the given retry, or `None` if the policy gives up
+    /// after `attempt` failed attempts.
+    fn delay(&self, attempt: u32) -> Option<RetryDelay> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        Some(match self.backoff {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, factor } => {
+                initial.scaled(factor.powi(attempt.saturating_sub(1) as i32))
+            }
+        })
+    }
+}
+
+/// Asset being loaded by the asset server.
+#[derive(Debug)]
+struct Work {
+    /// Asset path.
+    path: String,
+    /// Handle to the asset being loaded.
+    handle: HandleUntyped,
+    /// Number of attempts made to load the asset, including the current one.
+    attempt: u32,
+    /// Instant when the loader first requested the asset from the asset server.
+    started: Instant,
+    /// Dependencies of the asset to wait for, once the asset itself loaded and its dependencies
+    /// were resolved by an [`AssetDependenciesPlugin`].
+    ///
+    /// [`AssetDependenciesPlugin`]: crate::AssetDependenciesPlugin
+    dependencies: Option<Vec<HandleId>>,
+    /// Instant when the asset was requested again for a retry, until the asset server reports
+    /// the new attempt as started.
+    retry_time: Option<Instant>,
+}
+
+/// Number of bytes of an asset file loaded, out of its total size if known.
+#[derive(Debug, Default, Clone, Copy)]
+struct ByteCount {
+    /// Number of bytes read.
+    loaded: u64,
+    /// Size of the file, if known.
+    total: Option<u64>,
+    /// Was the size of the file looked up in the asset folder?
+    looked_up: bool,
+}
+
+/// Time constant of the smoothing of the estimated remaining time, in seconds.
+const ETA_TIME_CONSTANT: f32 = 1.0;
+
+/// Maximum estimated remaining time, in seconds, which the estimate is clamped to when very
+/// little work completed yet.
+const MAX_ETA_SECONDS: f32 = 86400.0;
+
+/// Smoothed estimate of the time remaining to complete some work, from its progress.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Eta {
+    /// Estimated remaining time in seconds, if known.
+    remaining: Option<f32>,
+    /// Time elapsed at the last update.
+    elapsed: Duration,
+}
+
+impl Eta {
+    /// Update the estimate from the current `progress` in \[0:1\], reached after `elapsed`.
+    ///
+    /// The raw estimate assumes the remaining work progresses at the same average rate as the
+    /// work already done. It's smoothed over time to avoid jumps each time some work completes,
+    /// while still counting down in between.
+    pub(crate) fn update(&mut self, progress: f32, elapsed: Duration) {
+        let dt = elapsed.saturating_sub(self.elapsed).as_secs_f32();
+        self.elapsed = elapsed;
+        if progress.is_nan() || progress <= 0.0 {
+            // Nothing completed yet, so no rate to estimate from
+            return;
+        }
+        let progress = progress.min(1.0);
+        let raw = (elapsed.as_secs_f32() * (1.0 - progress) / progress).min(MAX_ETA_SECONDS);
+        self.remaining = Some(match self.remaining {
+            Some(remaining) => {
+                let remaining = (remaining - dt).max(0.0);
+                remaining + (raw - remaining) * (1.0 - (-dt / ETA_TIME_CONSTANT).exp())
+            }
+            None => raw,
+        });
+    }
+
+    /// Mark the work as completed, with no remaining time.
+    pub(crate) fn complete(&mut self) {
+        self.remaining = Some(0.0);
+    }
+
+    /// Get the estimated remaining time, if known.
+    pub(crate) fn remaining(&self) -> Option<Duration> {
+        self.remaining
+            .map(|remaining| Duration::from_secs_f32(remaining.clamp(0.0, MAX_ETA_SECONDS)))
+    }
+}
+
+/// Point in time at which a failed asset is retried.
+#[derive(Debug, Clone, Copy)]
+enum Due {
+    /// Frame index of the loader.
+    Frame(u64),
+    /// Instant in time.
+    Time(Instant),
+}
+
+/// Asset which failed to load, and is waiting to be retried.
+#[derive(Debug)]
+struct Retry {
+    /// Asset path.
+    path: String,
+    /// Number of attempts already made to load the asset.
+    attempt: u32,
+    /// Point in time after which the asset is retried.
+    due: Due,
+    /// Instant when the loader first requested the asset from the asset server.
+    started: Instant,
+    /// Handle to the asset, if any, kept until the retry so the asset server doesn't free the
+    /// asset in-between, which would race with the new attempt.
+    handle: Option<HandleUntyped>,
+}
+
+/// Output of a task, type-erased to be stored alongside the outputs of other tasks.
+type TaskOutput = Box<dyn Any + Send>;
+
+/// Task pool a task runs on.
+#[derive(Debug, Clone, Copy)]
+enum TaskPoolKind {
+    /// The [`AsyncComputeTaskPool`].
+    Compute,
+    /// The [`IoTaskPool`].
+    Io,
+}
+
+/// Task enqueued into a [`Loader`], and not yet spawned on its task pool.
+struct PendingTask {
+    /// Task name.
+    name: String,
+    /// Task pool to spawn the task on.
+    pool: TaskPoolKind,
+    /// Future to run.
+    future: Pin<Box<dyn Future<Output = TaskOutput> + Send>>,
+}
+
+impl std::fmt::Debug for PendingTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingTask")
+            .field("name", &self.name)
+            .field("pool", &self.pool)
+            .finish()
+    }
+}
+
+/// Task running on its task pool.
+#[derive(Debug)]
+struct RunningTask {
+    /// Task name.
+    name: String,
+    /// Handle to the running task. Dropping it cancels the task.
+    task: Task<TaskOutput>,
+    /// Instant when the loader spawned the task.
+    started: Instant,
+}
+
+/// Event sent when some assets of a [`Loader`] batch did not finish loading before their deadline.
+///
+/// The timed out assets are marked as failed with [`FailureReason::TimedOut`].
+#[derive(Debug, Clone)]
+pub struct LoaderTimedOut {
+    /// Entity holding the [`Loader`] component.
+    pub entity: Entity,
+    /// Paths of the assets which were still pending when their deadline passed.
+    pub paths: Vec<String>,
+}
+
+/// Event sent when a [`Loader`] batch is cancelled with [`Loader::cancel()`].
+#[derive(Debug, Clone)]
+pub struct LoaderCancelled {
+    /// Entity holding the [`Loader`] component.
+    pub entity: Entity,
+    /// Paths of the assets and names of the tasks which never finished.
+    pub paths: Vec<String>,
 }
 
 /// Helper to load a group of assets together and wait for completion of all without
@@ -25,7 +460,52 @@ pub enum State {
 /// Once all requests are made, calling [`submit()`] starts the actual loading via the
 /// asset server. The loading state of the entire group can be queried with [`is_done()`];
 /// once that returns `true`, individual assets can be extracted from the [`Loader`]
-/// with [`take()`].
+/// with [`take()`]. The current [`LoaderState`] can be queried at any time with [`state()`].
+///
+/// Calling [`enqueue()`] or [`submit()`] out of order panics. Use [`try_enqueue()`] and
+/// [`try_submit()`] instead to get a [`LoaderError`] back. Once the batch is submitted, more
+/// assets can still be added to it with [`append()`] until it completes, or the batch can be
+/// abandoned with [`cancel()`].
+///
+/// An asset which fails to load is not considered loaded. Instead it is recorded in a
+/// separate failure set, which can be queried with [`has_failures()`] and [`failed_paths()`].
+/// Assets are required by default. Once all assets either loaded or failed, the batch
+/// completes; if any required asset failed then the batch itself failed, and
+/// [`is_batch_failed()`] returns `true` instead of [`is_done()`]. Assets enqueued with
+/// [`enqueue_optional()`] are nice to have; their failure doesn't fail the batch, so
+/// [`is_done()`] returning `true` does not guarantee that those are available.
+///
+/// By default all assets and tasks have the same weight in the [`progress()`]. Use
+/// [`enqueue_weighted()`] to give some assets a larger weight, for example to account for
+/// their size, or enable [`set_auto_weight()`] to weight assets by their file size.
+///
+/// Some assets like glTF scenes report being loaded while their dependencies are still loading.
+/// Add an [`AssetDependenciesPlugin`] for those asset types to also wait for their dependencies.
+///
+/// When the asset server reads its assets through a [`ProgressAssetIo`], the progress can also
+/// be measured in bytes with [`progress_bytes()`].
+///
+/// The time elapsed since the batch was submitted is available with [`elapsed()`], and an
+/// estimate of the time remaining until all assets and tasks complete with
+/// [`estimated_remaining()`], for example to display on a loading screen.
+///
+/// Failed assets can optionally be retried, by setting a [`RetryPolicy`] with
+/// [`set_retry_policy()`]. An asset waiting to be retried is still pending, and is
+/// only marked as failed once the policy gives up.
+///
+/// To avoid waiting forever on an asset which never finishes loading, a deadline can be set
+/// for each individual asset with [`set_asset_timeout()`], and for the entire batch with
+/// [`set_batch_timeout()`]. Assets still pending once their deadline passed are marked as
+/// failed, and a [`LoaderTimedOut`] event is sent.
+///
+/// A loader can be paused with [`pause()`], for example to avoid competing with gameplay for I/O.
+/// While paused, it doesn't start loading any new asset or task, but keeps tracking the ones
+/// already in flight, until resumed with [`resume()`].
+///
+/// Besides assets, a batch can contain custom asynchronous tasks enqueued with [`enqueue_task()`],
+/// for any non-asset work which needs to complete together with the assets, like parsing a
+/// configuration file. Tasks count toward [`pending_count()`] and [`progress()`] like assets,
+/// and their output can be taken with [`take_task()`] once they completed.
 ///
 /// The [`Loader`] will keep all assets loaded until they're consume with [`take()`], or
 /// the loader is reset with [`reset()`]. When reset, all pending and loaded assets are
@@ -51,8 +531,11 @@ pub enum State {
 /// fn update(mut query: Query<&mut Loader>) {
 ///   let mut loader = query.single_mut();
 ///   if loader.is_done() {
-///     let tex_handle = loader.take("texture.png").unwrap();
-///     let mesh_handle = loader.take("mesh.gltf").unwrap();
+///     if loader.has_failures() {
+///       error!("Failed to load assets: {:?}", loader.failed_paths());
+///     }
+///     let tex_handle = loader.take("texture.png").ok();
+///     let mesh_handle = loader.take("mesh.gltf").ok();
 ///     loader.reset(); // ensures is_done() returns false next time
 ///   }
 /// }
@@ -60,15 +543,42 @@ pub enum State {
 ///
 /// [`enqueue()`]: Loader::enqueue
 /// [`submit()`]: Loader::submit
+/// [`try_enqueue()`]: Loader::try_enqueue
+/// [`try_submit()`]: Loader::try_submit
+/// [`state()`]: Loader::state
 /// [`is_done()`]: Loader::is_done
 /// [`take()`]: Loader::take
 /// [`reset()`]: Loader::reset
+/// [`has_failures()`]: Loader::has_failures
+/// [`failed_paths()`]: Loader::failed_paths
+/// [`is_batch_failed()`]: Loader::is_batch_failed
+/// [`enqueue_optional()`]: Loader::enqueue_optional
+/// [`set_retry_policy()`]: Loader::set_retry_policy
+/// [`set_asset_timeout()`]: Loader::set_asset_timeout
+/// [`set_batch_timeout()`]: Loader::set_batch_timeout
+/// [`enqueue_task()`]: Loader::enqueue_task
+/// [`enqueue_weighted()`]: Loader::enqueue_weighted
+/// [`set_auto_weight()`]: Loader::set_auto_weight
+/// [`AssetDependenciesPlugin`]: crate::AssetDependenciesPlugin
+/// [`append()`]: Loader::append
+/// [`cancel()`]: Loader::cancel
+/// [`pause()`]: Loader::pause
+/// [`resume()`]: Loader::resume
+/// [`ProgressAssetIo`]: crate::ProgressAssetIo
+/// [`progress_bytes()`]: Loader::progress_bytes
+/// [`elapsed()`]: Loader::elapsed
+/// [`estimated_remaining()`]: Loader::estimated_remaining
+/// [`pending_count()`]: Loader::pending_count
+/// [`progress()`]: Loader::progress
+/// [`take_task()`]: Loader::take_task
 #[derive(Debug, Component)]
 pub struct Loader {
     /// Loader state.
-    state: RwLock<State>,
+    state: RwLock<LoaderState>,
     /// Number of pending load requests that did not complete yet.
     count: AtomicUsize,
+    /// Number of assets loaded successfully, including those already taken.
+    loaded: AtomicUsize,
     /// Total number of requests once [`submit()`] is called.
     ///
     /// [`submit()`]: Loader::submit()
@@ -76,20 +586,124 @@ pub struct Loader {
     /// Request queue containing the assets not yet queried to the asset server.
     request_queue: Mutex<Vec<String>>,
     /// Work queue for assets being loaded by the asset server.
-    work_queue: Mutex<Vec<(String, HandleUntyped)>>,
+    work_queue: Mutex<Vec<Work>>,
+    /// Retry queue for assets which failed to load, and are waiting to be retried.
+    retry_queue: Mutex<Vec<Retry>>,
     /// Completion queue keeping assets loaded after they're removed from the work queue.
     complete_queue: Mutex<HashMap<String, HandleUntyped>>,
+    /// Failure set keeping the reason why some assets failed to load.
+    failed_queue: Mutex<HashMap<String, FailureReason>>,
+    /// Paths of the optional assets, whose failure doesn't fail the batch.
+    optional: HashSet<String>,
+    /// Paths of the assets and names of the tasks already taken out of the loader.
+    taken: HashSet<String>,
+    /// Policy for retrying failed assets.
+    retry_policy: RetryPolicy,
+    /// Number of times the loader was updated, used to schedule retries.
+    frame: u64,
+    /// Maximum duration an individual asset can take to load.
+    asset_timeout: Option<Duration>,
+    /// Maximum duration the entire batch can take to load, from [`submit()`].
+    ///
+    /// [`submit()`]: Loader::submit()
+    batch_timeout: Option<Duration>,
+    /// Instant when the batch was submitted.
+    submit_time: Option<Instant>,
+    /// Paths of the assets which timed out since the last update, not yet notified.
+    timed_out: Mutex<Vec<String>>,
+    /// Paths of the assets and names of the tasks dropped by [`cancel()`], not yet notified.
+    ///
+    /// [`cancel()`]: Loader::cancel()
+    cancelled: Mutex<Vec<String>>,
+    /// Progress of the batch when it was cancelled, if it was.
+    cancelled_progress: Option<f32>,
+    /// Tasks enqueued and not yet spawned on their task pool.
+    task_queue: Mutex<Vec<PendingTask>>,
+    /// Tasks running on their task pool.
+    running_tasks: Mutex<Vec<RunningTask>>,
+    /// Outputs of the completed tasks, keyed by task name.
+    task_outputs: Mutex<HashMap<String, TaskOutput>>,
+    /// Weights of the pending assets and tasks, keyed by asset path or task name.
+    pending_weights: Mutex<HashMap<String, f32>>,
+    /// Total weight of all assets and tasks once [`submit()`] is called.
+    ///
+    /// [`submit()`]: Loader::submit()
+    total_weight: f32,
+    /// Weight assets without explicit weight by their file size?
+    auto_weight: bool,
+    /// Paths of the assets whose weight is to be determined from their file size.
+    auto_weighted: Vec<String>,
+    /// Count the dependencies of the assets in the progress?
+    count_dependencies: bool,
+    /// Number of bytes loaded for each asset file of the batch.
+    bytes: HashMap<PathBuf, ByteCount>,
+    /// Time it took each asset or task to complete from [`submit()`], keyed by asset path or
+    /// task name.
+    ///
+    /// [`submit()`]: Loader::submit()
+    finish_times: Mutex<HashMap<String, Duration>>,
+    /// Instant when the last asset or task of the batch completed.
+    end_time: Mutex<Option<Instant>>,
+    /// Estimated time remaining until the batch completes.
+    eta: Eta,
+    /// Instant when the loader was paused, if currently paused.
+    paused_since: Option<Instant>,
+    /// Time spent paused since the batch was submitted, excluding the current pause if any.
+    paused_for: Duration,
+    /// Intervals during which the loader was paused since the batch was submitted, excluding the
+    /// current pause if any.
+    pauses: Vec<(Instant, Instant)>,
+    /// Paths of the assets and names of the tasks enqueued without explicit weight.
+    default_weighted: Vec<String>,
+    /// Expected duration of the batch, from the [`LoadTimings`] of a previous run.
+    expected_duration: Option<Duration>,
+    /// Were the [`LoadTimings`] applied to the batch?
+    timings_applied: bool,
+    /// Were the timings of the batch recorded into the [`LoadTimings`]?
+    timings_recorded: bool,
 }
 
 impl Default for Loader {
     fn default() -> Self {
         Loader {
-            state: RwLock::new(State::Ready),
+            state: RwLock::new(LoaderState::Ready),
             count: AtomicUsize::new(0),
+            loaded: AtomicUsize::new(0),
             total: 0,
             request_queue: Mutex::new(vec![]),
             work_queue: Mutex::new(vec![]),
+            retry_queue: Mutex::new(vec![]),
             complete_queue: Mutex::new(HashMap::new()),
+            failed_queue: Mutex::new(HashMap::new()),
+            optional: HashSet::new(),
+            taken: HashSet::new(),
+            retry_policy: RetryPolicy::default(),
+            frame: 0,
+            asset_timeout: None,
+            batch_timeout: None,
+            submit_time: None,
+            timed_out: Mutex::new(vec![]),
+            cancelled: Mutex::new(vec![]),
+            cancelled_progress: None,
+            task_queue: Mutex::new(vec![]),
+            running_tasks: Mutex::new(vec![]),
+            task_outputs: Mutex::new(HashMap::new()),
+            pending_weights: Mutex::new(HashMap::new()),
+            total_weight: 0.0,
+            auto_weight: false,
+            auto_weighted: vec![],
+            count_dependencies: false,
+            bytes: HashMap::new(),
+            finish_times: Mutex::new(HashMap::new()),
+            end_time: Mutex::new(None),
+            eta: Eta::default(),
+            paused_since: None,
+            paused_for: Duration::ZERO,
+            pauses: vec![],
+            default_weighted: vec![],
+            expected_duration: None,
+            timings_applied: false,
+            timings_recorded: false,
         }
     }
 }
@@ -100,6 +714,67 @@ impl Loader {
         Self::default()
     }
 
+    /// Cancel the batch being loaded.
+    ///
+    /// All the assets and tasks not yet completed are dropped: their handles are released, and
+    /// running tasks are cancelled. The loader moves to the [`LoaderState::Cancelled`] state,
+    /// and a [`LoaderCancelled`] event listing the assets and tasks which never finished is
+    /// sent on the next update, even if the loader is [`reset()`] in between. The assets which
+    /// already loaded are kept until the loader is reset, and can still be taken with
+    /// [`take()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_bootloader::*;
+    /// let mut loader = Loader::new();
+    /// loader.enqueue("level2.gltf");
+    /// loader.submit();
+    /// // ...the player backs out of the level load...
+    /// loader.cancel();
+    /// assert_eq!(loader.state(), LoaderState::Cancelled);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the loader is not loading a batch. See [`try_cancel()`] for a
+    /// non-panicking variant.
+    ///
+    /// [`reset()`]: Loader::reset
+    /// [`take()`]: Loader::take
+    /// [`try_cancel()`]: Loader::try_cancel
+    pub fn cancel(&mut self) {
+        if let Err(err) = self.try_cancel() {
+            panic!("Failed to cancel loader batch: {}", err);
+        }
+    }
+
+    /// Try to cancel the batch being loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LoaderError::NotLoading`] if the loader is not loading a batch.
+    pub fn try_cancel(&mut self) -> Result<(), LoaderError> {
+        let mut state = self.state.write();
+        if *state != LoaderState::Loading {
+            return Err(LoaderError::NotLoading);
+        }
+        let mut paths: Vec<String> = std::mem::take(&mut *self.request_queue.lock());
+        paths.extend(self.work_queue.lock().drain(..).map(|work| work.path));
+        paths.extend(self.retry_queue.lock().drain(..).map(|retry| retry.path));
+        paths.extend(self.task_queue.lock().drain(..).map(|task| task.name));
+        paths.extend(self.running_tasks.lock().drain(..).map(|task| task.name));
+        info!("Loader batch cancelled: {} pending", paths.len());
+        self.cancelled_progress = Some(self.progress());
+        self.count.store(0, Ordering::Release);
+        self.pending_weights.lock().clear();
+        self.auto_weighted.clear();
+        *self.end_time.lock() = Some(Instant::now());
+        self.cancelled.lock().extend(paths);
+        *state = LoaderState::Cancelled;
+        Ok(())
+    }
+
     /// Reset the loader to its idle state. This allows submitting a new batch of asset loading requests.
     /// All pending requests and already loaded assets are forgotten. If the assets were already loaded,
     /// and were not consumed with [`take`], the last reference may be dropped and they may get unloaded
@@ -108,183 +783,1821 @@ impl Loader {
     /// [`take`]: Loader::take
     pub fn reset(&mut self) {
         let mut state = self.state.write();
-        if *state != State::Ready {
+        if *state != LoaderState::Ready {
             self.request_queue.lock().clear();
             self.work_queue.lock().clear();
+            self.retry_queue.lock().clear();
             self.count.store(0, Ordering::Release);
+            self.loaded.store(0, Ordering::Release);
             self.total = 0;
+            self.submit_time = None;
+            self.timed_out.lock().clear();
+            self.cancelled_progress = None;
             self.complete_queue.lock().clear();
-            *state = State::Ready;
+            self.failed_queue.lock().clear();
+            self.optional.clear();
+            self.taken.clear();
+            self.task_queue.lock().clear();
+            self.running_tasks.lock().clear();
+            self.task_outputs.lock().clear();
+            self.pending_weights.lock().clear();
+            self.total_weight = 0.0;
+            self.auto_weighted.clear();
+            self.bytes.clear();
+            self.finish_times.lock().clear();
+            *self.end_time.lock() = None;
+            self.eta = Eta::default();
+            self.default_weighted.clear();
+            self.expected_duration = None;
+            self.timings_applied = false;
+            self.timings_recorded = false;
+            *state = LoaderState::Ready;
         }
     }
 
-    /// Enqueue a new asset loading request.
-    ///
-    /// # Panics
-    ///
-    /// This method panics if the loader is not in the idle state.
-    pub fn enqueue(&mut self, path: &str) {
-        assert!(*self.state.read() == State::Ready);
-        self.request_queue.lock().push(path.to_owned());
-        self.count.fetch_add(1, Ordering::Release);
-        trace!(
-            "Enqueued request: {} ({}/{})",
-            path,
-            self.request_queue.lock().len(),
-            self.count.load(Ordering::Relaxed)
-        );
+    /// Get the policy for retrying failed assets.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
     }
 
-    /// Submit the pending batch of asset loading requests. After this, no new request can be
-    /// enqueued until [`reset`] is called.
-    ///
-    /// # Panics
+    /// Set the policy for retrying failed assets.
     ///
-    /// This method panics if the loader is not in the idle state.
+    /// The policy is not reset by [`reset()`], and applies to all subsequent batches.
     ///
-    /// [`reset`]: Loader::reset
-    pub fn submit(&mut self) {
-        self.total = self.request_queue.lock().len();
-        let mut state = self.state.write();
-        assert!(*state == State::Ready);
-        *state = State::Loading;
+    /// [`reset()`]: Loader::reset
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
     }
 
-    /// Is the loader empty? Returns `true` if there is no pending asset loading request.
-    ///
-    /// This is equivalent to [`pending_count() == 0`].
-    ///
-    /// [`pending_count() == 0`]: Loader::pending_count
-    pub fn is_empty(&self) -> bool {
-        self.pending_count() == 0
+    /// Get the maximum duration an individual asset can take to load, if any.
+    pub fn asset_timeout(&self) -> Option<Duration> {
+        self.asset_timeout
     }
 
-    /// Total number of asset loading requests submitted.
-    pub fn total_count(&self) -> usize {
-        self.total
+    /// Set the maximum duration an individual asset or task can take to load, or `None` for no
+    /// limit.
+    ///
+    /// Unlike the deadline of [`set_batch_timeout()`], which is measured from [`submit()`], this
+    /// duration is measured separately for each asset from the moment the loader first requests
+    /// it from the asset server, and includes any retry. For the assets enqueued before
+    /// [`submit()`] this is the first update after it, while an asset appended later gets its
+    /// full duration from the update after it's appended. Tasks are measured from the moment
+    /// they're spawned. Like for the batch timeout, the time spent paused (see [`pause()`])
+    /// doesn't count toward this duration. Once the deadline passes, the asset is marked as
+    /// failed with [`FailureReason::TimedOut`], and is not retried.
+    ///
+    /// [`set_batch_timeout()`]: Loader::set_batch_timeout
+    /// [`submit()`]: Loader::submit
+    /// [`pause()`]: Loader::pause
+    pub fn set_asset_timeout(&mut self, timeout: Option<Duration>) {
+        self.asset_timeout = timeout;
     }
 
-    /// Number of pending asset loading requests not yet completed.
-    pub fn pending_count(&self) -> usize {
-        self.count.load(Ordering::Acquire)
+    /// Get the maximum duration the entire batch can take to load, if any.
+    pub fn batch_timeout(&self) -> Option<Duration> {
+        self.batch_timeout
     }
 
-    /// Return loading progress, in \[0:1\].
+    /// Set the maximum duration the entire batch can take to load, or `None` for no limit.
+    ///
+    /// The duration is measured from the call to [`submit()`]. Once the deadline passes, all
+    /// pending assets are marked as failed with [`FailureReason::TimedOut`], and the batch
+    /// completes.
     ///
     /// # Example
     ///
     /// ```
+    /// # use bevy::{prelude::*, utils::Duration};
     /// # use bevy_bootloader::*;
-    /// # let loader = Loader::new();
-    /// println!("Progress: {}%", loader.progress() * 100.0);
+    /// let mut loader = Loader::new();
+    /// loader.set_asset_timeout(Some(Duration::from_secs(10)));
+    /// loader.set_batch_timeout(Some(Duration::from_secs(30)));
+    /// loader.enqueue("logo.png");
+    /// loader.submit();
     /// ```
-    pub fn progress(&self) -> f32 {
-        let remain = self.count.load(Ordering::Relaxed);
-        if self.total > 0 {
-            (self.total - remain) as f32 / self.total as f32
-        } else {
-            1.0
+    ///
+    /// [`submit()`]: Loader::submit
+    pub fn set_batch_timeout(&mut self, timeout: Option<Duration>) {
+        self.batch_timeout = timeout;
+    }
+
+    /// Is automatic weighting of assets by file size enabled?
+    pub fn auto_weight(&self) -> bool {
+        self.auto_weight
+    }
+
+    /// Enable or disable automatic weighting of assets by file size.
+    ///
+    /// When enabled, assets enqueued without an explicit weight get a weight equal to their file
+    /// size in bytes once the batch starts loading, so that [`progress()`] reflects the amount of
+    /// data loaded. The file size is read from the folder of the default file-based asset I/O,
+    /// configured by [`AssetServerSettings`]. Assets whose size can't be determined, for example
+    /// with a custom asset I/O or on the web, get the average weight of the other assets, or a
+    /// weight of `1.0` if no size is known. Explicit weights given to [`enqueue_weighted()`] are
+    /// used as is, so should be expressed in bytes too.
+    ///
+    /// This setting is not reset by [`reset()`], and applies to all subsequent batches.
+    ///
+    /// [`progress()`]: Loader::progress
+    /// [`enqueue_weighted()`]: Loader::enqueue_weighted
+    /// [`reset()`]: Loader::reset
+    pub fn set_auto_weight(&mut self, auto_weight: bool) {
+        self.auto_weight = auto_weight;
+    }
+
+    /// Are the dependencies of the assets counted in the progress?
+    pub fn count_dependencies(&self) -> bool {
+        self.count_dependencies
+    }
+
+    /// Count the dependencies of the assets in the progress.
+    ///
+    /// When enabled, each dependency resolved by an [`AssetDependenciesPlugin`] adds a weight
+    /// of `1.0` to the [`total_weight()`], and counts as completed once loaded. Because
+    /// dependencies are only known once their parent asset loaded, the [`progress()`] may go
+    /// backward when new dependencies are discovered.
+    ///
+    /// This setting is not reset by [`reset()`], and applies to all subsequent batches.
+    ///
+    /// [`AssetDependenciesPlugin`]: crate::AssetDependenciesPlugin
+    /// [`total_weight()`]: Loader::total_weight
+    /// [`progress()`]: Loader::progress
+    /// [`reset()`]: Loader::reset
+    pub fn set_count_dependencies(&mut self, count_dependencies: bool) {
+        self.count_dependencies = count_dependencies;
+    }
+
+    /// Get the current lifecycle state of the loader.
+    pub fn state(&self) -> LoaderState {
+        *self.state.read()
+    }
+
+    /// Check that the loader is in the idle state and can accept new requests.
+    fn check_ready(&self) -> Result<(), LoaderError> {
+        match *self.state.read() {
+            LoaderState::Ready => Ok(()),
+            LoaderState::Loading => Err(LoaderError::AlreadySubmitted),
+            LoaderState::Done | LoaderState::Failed | LoaderState::Cancelled => {
+                Err(LoaderError::NotReady)
+            }
         }
     }
 
-    /// Is the loader done loading the current asset batch?
-    pub fn is_done(&self) -> bool {
-        *self.state.read() == State::Done
+    /// Enqueue a new loading request for a required asset. If the asset fails to load,
+    /// the entire batch fails.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the loader is not in the idle state. A path which was already
+    /// enqueued is ignored with a warning. See [`try_enqueue()`] for a non-panicking variant.
+    ///
+    /// [`try_enqueue()`]: Loader::try_enqueue
+    pub fn enqueue(&mut self, path: &str) {
+        expect_enqueued(self.try_enqueue(path), "asset", path);
     }
 
-    /// Check if the asset with the given path was loaded already.
+    /// Try to enqueue a new loading request for a required asset. If the asset fails to load,
+    /// the entire batch fails.
     ///
     /// # Example
     ///
     /// ```
     /// # use bevy_bootloader::*;
-    /// # let mut loader = Loader::new();
-    /// if loader.is_loaded("image.png") {
-    ///     let handle = loader.take("image.png").unwrap();
-    /// }
+    /// let mut loader = Loader::new();
+    /// assert!(loader.try_enqueue("logo.png").is_ok());
+    /// assert_eq!(
+    ///     loader.try_enqueue("logo.png"),
+    ///     Err(LoaderError::DuplicatePath("logo.png".to_string()))
+    /// );
+    /// loader.submit();
+    /// assert_eq!(loader.try_enqueue("music.ogg"), Err(LoaderError::AlreadySubmitted));
     /// ```
-    pub fn is_loaded(&self, path: &str) -> bool {
-        self.complete_queue.lock().contains_key(path)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LoaderError::AlreadySubmitted`] or [`LoaderError::NotReady`] if the loader is
+    /// not in the idle state, and [`LoaderError::DuplicatePath`] if the path was already enqueued.
+    pub fn try_enqueue(&mut self, path: &str) -> Result<(), LoaderError> {
+        self.push_request(path, None)
     }
 
-    /// Take the asset with the given path, if found and loaded, and remove its handle from the loader.
-    /// After this, the loader will forget about that asset and not keep it loaded anymore.
+    /// Enqueue a new loading request for a required asset, with the given weight in the
+    /// [`progress()`]. The default weight of an asset enqueued with [`enqueue()`] is `1.0`.
     ///
-    /// This method returns an untyped handle because the [`Loader`] needs to be able to store internally
-    /// an heterogeneous collection of assets. Cast the handle to the expected type if needed:
+    /// # Example
     ///
     /// ```
-    /// # use bevy::prelude::*;
     /// # use bevy_bootloader::*;
-    /// # let mut loader = Loader::new();
-    /// if let Some(handle) = loader.take("image.png") {
-    ///     let image_handle = handle.typed::<Image>();
-    /// }
+    /// let mut loader = Loader::new();
+    /// loader.enqueue_weighted("config.json", 1.0);
+    /// loader.enqueue_weighted("music.ogg", 50.0); // much larger
+    /// loader.submit();
+    /// assert_eq!(loader.total_weight(), 51.0);
     /// ```
-    pub fn take(&mut self, path: &str) -> Option<HandleUntyped> {
-        self.complete_queue.lock().remove(path)
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the loader is not in the idle state. A path which was already
+    /// enqueued is ignored with a warning. See [`try_enqueue_weighted()`] for a non-panicking variant.
+    ///
+    /// [`progress()`]: Loader::progress
+    /// [`enqueue()`]: Loader::enqueue
+    /// [`try_enqueue_weighted()`]: Loader::try_enqueue_weighted
+    pub fn enqueue_weighted(&mut self, path: &str, weight: f32) {
+        expect_enqueued(self.try_enqueue_weighted(path, weight), "asset", path);
     }
 
-    fn tick(&mut self, asset_server: &AssetServer) {
-        // Check pending asset loading requests and remove completed ones
-        {
-            let mut work_queue = self.work_queue.lock();
-            // TODO - Vec::drain_filter()
-            let mut i = 0;
-            while i < work_queue.len() {
-                let (path, handle) = &work_queue[i];
-                let state = asset_server.get_load_state(handle);
-                if state == bevy::asset::LoadState::Loaded
-                    || state == bevy::asset::LoadState::Failed
-                {
-                    trace!("Asset finished loading: {} {:?}", path, handle);
-                    let (path, handle) = work_queue.remove(i);
-                    self.complete_queue.lock().insert(path, handle);
-                    if self.count.fetch_sub(1, Ordering::Acquire) == 1 {
-                        // Last asset loaded, all done
-                        *self.state.write() = State::Done;
-                    }
-                } else {
-                    i += 1;
-                }
+    /// Try to enqueue a new loading request for a required asset, with the given weight in the
+    /// [`progress()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`try_enqueue()`].
+    ///
+    /// [`progress()`]: Loader::progress
+    /// [`try_enqueue()`]: Loader::try_enqueue
+    pub fn try_enqueue_weighted(&mut self, path: &str, weight: f32) -> Result<(), LoaderError> {
+        self.push_request(path, Some(weight))
+    }
+
+    /// Enqueue a loading request, with an optional explicit weight.
+    fn push_request(&mut self, path: &str, weight: Option<f32>) -> Result<(), LoaderError> {
+        self.check_ready()?;
+        self.check_unique(path)?;
+        let weight = self.request_weight(path, weight);
+        self.pending_weights.lock().insert(path.to_owned(), weight);
+        let mut request_queue = self.request_queue.lock();
+        request_queue.push(path.to_owned());
+        self.count.fetch_add(1, Ordering::Release);
+        trace!(
+            "Enqueued request: {} ({}/{})",
+            path,
+            request_queue.len(),
+            self.count.load(Ordering::Relaxed)
+        );
+        Ok(())
+    }
+
+    /// Enqueue a new loading request for an optional asset. If the asset fails to load,
+    /// it is recorded as failed like any other asset, but the batch doesn't fail.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_bootloader::*;
+    /// let mut loader = Loader::new();
+    /// loader.enqueue("ui_atlas.png"); // critical
+    /// loader.enqueue_optional("menu_music.ogg"); // nice to have
+    /// loader.submit();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the loader is not in the idle state. A path which was already
+    /// enqueued is ignored with a warning. See [`try_enqueue_optional()`] for a non-panicking variant.
+    ///
+    /// [`try_enqueue_optional()`]: Loader::try_enqueue_optional
+    pub fn enqueue_optional(&mut self, path: &str) {
+        expect_enqueued(self.try_enqueue_optional(path), "asset", path);
+    }
+
+    /// Try to enqueue a new loading request for an optional asset. If the asset fails to load,
+    /// it is recorded as failed like any other asset, but the batch doesn't fail.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`try_enqueue()`].
+    ///
+    /// [`try_enqueue()`]: Loader::try_enqueue
+    pub fn try_enqueue_optional(&mut self, path: &str) -> Result<(), LoaderError> {
+        self.try_enqueue(path)?;
+        self.optional.insert(path.to_owned());
+        Ok(())
+    }
+
+    /// Append a new loading request for a required asset to the batch being loaded.
+    ///
+    /// Unlike [`enqueue()`], this can be called once the batch was submitted, while it's still
+    /// loading, for example to load the assets referenced by an asset of the batch once that one
+    /// loaded. The appended asset is part of the same batch, and updates [`total_count()`] and
+    /// [`total_weight()`], so the [`progress()`] may go backward. The asset is weighted like
+    /// enqueued assets, from its file size with [`set_auto_weight()`] or from its timing with the
+    /// [`LoadTimingsPlugin`], and otherwise has a weight of `1.0`; use [`append_weighted()`] to
+    /// specify another weight. Before the batch is submitted, this is equivalent to
+    /// [`enqueue()`].
+    ///
+    /// Once the last asset of the batch completed the batch is done, and no asset can be
+    /// appended anymore.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_bootloader::*;
+    /// let mut loader = Loader::new();
+    /// loader.enqueue("level.ron");
+    /// loader.submit();
+    /// // ...later, once "level.ron" is loaded and read...
+    /// loader.append("level/terrain.png");
+    /// assert_eq!(loader.total_count(), 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the batch already completed, or if the path was already part of
+    /// the batch. See [`try_append()`] for a non-panicking variant.
+    ///
+    /// [`enqueue()`]: Loader::enqueue
+    /// [`total_count()`]: Loader::total_count
+    /// [`total_weight()`]: Loader::total_weight
+    /// [`progress()`]: Loader::progress
+    /// [`set_auto_weight()`]: Loader::set_auto_weight
+    /// [`LoadTimingsPlugin`]: crate::LoadTimingsPlugin
+    /// [`append_weighted()`]: Loader::append_weighted
+    /// [`try_append()`]: Loader::try_append
+    pub fn append(&mut self, path: &str) {
+        if let Err(err) = self.try_append(path) {
+            panic!("Failed to append asset '{}': {}", path, err);
+        }
+    }
+
+    /// Try to append a new loading request for a required asset to the batch being loaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LoaderError::NotReady`] if the batch already completed, and
+    /// [`LoaderError::DuplicatePath`] if the path was already part of the batch, even if the
+    /// asset was since taken out of the loader.
+    pub fn try_append(&mut self, path: &str) -> Result<(), LoaderError> {
+        self.append_request(path, None)
+    }
+
+    /// Append a new loading request for a required asset to the batch being loaded, with the
+    /// given weight in the [`progress()`].
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the batch already completed, or if the path was already part of
+    /// the batch. See [`try_append_weighted()`] for a non-panicking variant.
+    ///
+    /// [`progress()`]: Loader::progress
+    /// [`try_append_weighted()`]: Loader::try_append_weighted
+    pub fn append_weighted(&mut self, path: &str, weight: f32) {
+        if let Err(err) = self.try_append_weighted(path, weight) {
+            panic!("Failed to append asset '{}': {}", path, err);
+        }
+    }
+
+    /// Try to append a new loading request for a required asset to the batch being loaded, with
+    /// the given weight in the [`progress()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`try_append()`].
+    ///
+    /// [`progress()`]: Loader::progress
+    /// [`try_append()`]: Loader::try_append
+    pub fn try_append_weighted(&mut self, path: &str, weight: f32) -> Result<(), LoaderError> {
+        self.append_request(path, Some(weight))
+    }
+
+    /// Get the initial weight of an asset request, and register an asset without explicit
+    /// weight to be weighted later from its file size or its timing, if enabled.
+    fn request_weight(&mut self, path: &str, weight: Option<f32>) -> f32 {
+        match weight {
+            Some(weight) => weight.max(0.0),
+            None => {
+                if self.auto_weight {
+                    self.auto_weighted.push(path.to_owned());
+                }
+                self.default_weighted.push(path.to_owned());
+                1.0
+            }
+        }
+    }
+
+    /// Append a loading request to the batch being loaded, with an optional explicit weight.
+    fn append_request(&mut self, path: &str, weight: Option<f32>) -> Result<(), LoaderError> {
+        match self.state() {
+            LoaderState::Ready => return self.push_request(path, weight),
+            LoaderState::Loading => {}
+            LoaderState::Done | LoaderState::Failed | LoaderState::Cancelled => {
+                return Err(LoaderError::NotReady)
+            }
+        }
+        self.check_unique(path)?;
+        let weight = self.request_weight(path, weight);
+        self.pending_weights.lock().insert(path.to_owned(), weight);
+        self.total_weight += weight;
+        self.total += 1;
+        self.bytes.entry(file_path(path)).or_default();
+        self.request_queue.lock().push(path.to_owned());
+        self.count.fetch_add(1, Ordering::Release);
+        trace!(
+            "Appended request: {} ({}/{})",
+            path,
+            self.count.load(Ordering::Relaxed),
+            self.total
+        );
+        Ok(())
+    }
+
+    /// Check that no asset path nor task name equal to `name` is already part of the batch.
+    fn check_unique(&self, name: &str) -> Result<(), LoaderError> {
+        if self.request_queue.lock().iter().any(|p| p == name)
+            || self.task_queue.lock().iter().any(|t| t.name == name)
+            || self.pending_weights.lock().contains_key(name)
+            || self.complete_queue.lock().contains_key(name)
+            || self.failed_queue.lock().contains_key(name)
+            || self.task_outputs.lock().contains_key(name)
+            || self.taken.contains(name)
+        {
+            return Err(LoaderError::DuplicatePath(name.to_owned()));
+        }
+        Ok(())
+    }
+
+    /// Enqueue a custom task running the given future on the [`AsyncComputeTaskPool`].
+    ///
+    /// The task is spawned once the batch is submitted, and counts toward [`pending_count()`] and
+    /// [`progress()`] like an asset. Once completed, its output can be taken with [`take_task()`].
+    /// Task names share the same namespace as asset paths, and must be unique within a batch.
+    ///
+    /// Use [`enqueue_io_task()`] instead for tasks mostly waiting on I/O.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_bootloader::*;
+    /// let mut loader = Loader::new();
+    /// loader.enqueue("logo.png");
+    /// loader.enqueue_task("config", async { 42_u32 });
+    /// loader.submit();
+    /// // ...later, once done...
+    /// # fn later(mut loader: Loader) {
+    /// let config: u32 = loader.take_task("config").unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the loader is not in the idle state. A name which was already
+    /// enqueued is ignored with a warning. See [`try_enqueue_task()`] for a non-panicking variant.
+    ///
+    /// [`pending_count()`]: Loader::pending_count
+    /// [`progress()`]: Loader::progress
+    /// [`take_task()`]: Loader::take_task
+    /// [`enqueue_io_task()`]: Loader::enqueue_io_task
+    /// [`try_enqueue_task()`]: Loader::try_enqueue_task
+    pub fn enqueue_task<T, F>(&mut self, name: &str, future: F)
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        expect_enqueued(self.try_enqueue_task(name, future), "task", name);
+    }
+
+    /// Try to enqueue a custom task running the given future on the [`AsyncComputeTaskPool`].
+    ///
+    /// See [`enqueue_task()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`try_enqueue()`].
+    ///
+    /// [`enqueue_task()`]: Loader::enqueue_task
+    /// [`try_enqueue()`]: Loader::try_enqueue
+    pub fn try_enqueue_task<T, F>(&mut self, name: &str, future: F) -> Result<(), LoaderError>
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.push_task(name, TaskPoolKind::Compute, future)
+    }
+
+    /// Enqueue a custom task running the given future on the [`IoTaskPool`].
+    ///
+    /// This is similar to [`enqueue_task()`], for tasks mostly waiting on I/O.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the loader is not in the idle state. A name which was already
+    /// enqueued is ignored with a warning. See [`try_enqueue_io_task()`] for a non-panicking variant.
+    ///
+    /// [`enqueue_task()`]: Loader::enqueue_task
+    /// [`try_enqueue_io_task()`]: Loader::try_enqueue_io_task
+    pub fn enqueue_io_task<T, F>(&mut self, name: &str, future: F)
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        expect_enqueued(self.try_enqueue_io_task(name, future), "task", name);
+    }
+
+    /// Try to enqueue a custom task running the given future on the [`IoTaskPool`].
+    ///
+    /// See [`enqueue_io_task()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`try_enqueue()`].
+    ///
+    /// [`enqueue_io_task()`]: Loader::enqueue_io_task
+    /// [`try_enqueue()`]: Loader::try_enqueue
+    pub fn try_enqueue_io_task<T, F>(&mut self, name: &str, future: F) -> Result<(), LoaderError>
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.push_task(name, TaskPoolKind::Io, future)
+    }
+
+    /// Enqueue a task to spawn on the given pool once the batch is submitted.
+    fn push_task<T, F>(
+        &mut self,
+        name: &str,
+        pool: TaskPoolKind,
+        future: F,
+    ) -> Result<(), LoaderError>
+    where
+        T: Send + 'static,
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.check_ready()?;
+        self.check_unique(name)?;
+        self.pending_weights.lock().insert(name.to_owned(), 1.0);
+        self.default_weighted.push(name.to_owned());
+        self.task_queue.lock().push(PendingTask {
+            name: name.to_owned(),
+            pool,
+            future: Box::pin(async move {
+                let output: TaskOutput = Box::new(future.await);
+                output
+            }),
+        });
+        self.count.fetch_add(1, Ordering::Release);
+        trace!("Enqueued task: {} ({:?})", name, pool);
+        Ok(())
+    }
+
+    /// Submit the pending batch of asset loading requests. After this, no new request can be
+    /// enqueued until [`reset`] is called.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the loader is not in the idle state. See [`try_submit()`] for a
+    /// non-panicking variant.
+    ///
+    /// [`reset`]: Loader::reset
+    /// [`try_submit()`]: Loader::try_submit
+    pub fn submit(&mut self) {
+        if let Err(err) = self.try_submit() {
+            panic!("Failed to submit loader batch: {}", err);
+        }
+    }
+
+    /// Try to submit the pending batch of asset loading requests. After this, no new request
+    /// can be enqueued until [`reset`] is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LoaderError::AlreadySubmitted`] or [`LoaderError::NotReady`] if the loader is
+    /// not in the idle state.
+    ///
+    /// [`reset`]: Loader::reset
+    pub fn try_submit(&mut self) -> Result<(), LoaderError> {
+        let mut state = self.state.write();
+        match *state {
+            LoaderState::Ready => {}
+            LoaderState::Loading => return Err(LoaderError::AlreadySubmitted),
+            LoaderState::Done | LoaderState::Failed | LoaderState::Cancelled => {
+                return Err(LoaderError::NotReady)
+            }
+        }
+        self.total = self.request_queue.lock().len() + self.task_queue.lock().len();
+        self.total_weight = self.pending_weights.lock().values().sum();
+        self.bytes = self
+            .request_queue
+            .lock()
+            .iter()
+            .map(|path| (file_path(path), ByteCount::default()))
+            .collect();
+        // An empty batch has nothing to wait for
+        *state = if self.total == 0 {
+            LoaderState::Done
+        } else {
+            LoaderState::Loading
+        };
+        let now = Instant::now();
+        self.submit_time = Some(now);
+        // Only count the time paused since the batch was submitted
+        self.paused_for = Duration::ZERO;
+        self.pauses.clear();
+        if self.paused_since.is_some() {
+            self.paused_since = Some(now);
+        }
+        Ok(())
+    }
+
+    /// Was the current asset batch cancelled with [`cancel()`]?
+    ///
+    /// [`cancel()`]: Loader::cancel
+    pub fn is_cancelled(&self) -> bool {
+        *self.state.read() == LoaderState::Cancelled
+    }
+
+    /// Is the loader empty? Returns `true` if there is no pending asset loading request.
+    ///
+    /// This is equivalent to [`pending_count() == 0`].
+    ///
+    /// [`pending_count() == 0`]: Loader::pending_count
+    pub fn is_empty(&self) -> bool {
+        self.pending_count() == 0
+    }
+
+    /// Total number of asset loading requests submitted.
+    pub fn total_count(&self) -> usize {
+        self.total
+    }
+
+    /// Number of assets of the batch which loaded successfully, including those already taken.
+    ///
+    /// Unlike [`total_count()`], this doesn't count tasks, nor assets which failed to load or
+    /// are still pending.
+    ///
+    /// [`total_count()`]: Loader::total_count
+    pub fn loaded_count(&self) -> usize {
+        self.loaded.load(Ordering::Acquire)
+    }
+
+    /// Number of pending asset loading requests not yet completed.
+    ///
+    /// This includes assets which failed to load and are waiting to be retried.
+    pub fn pending_count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    /// Return loading progress, in \[0:1\].
+    ///
+    /// Once the batch is cancelled with [`cancel()`], this stays at its value when cancelled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_bootloader::*;
+    /// # let loader = Loader::new();
+    /// println!("Progress: {}%", loader.progress() * 100.0);
+    /// ```
+    ///
+    /// [`cancel()`]: Loader::cancel
+    pub fn progress(&self) -> f32 {
+        if let Some(progress) = self.cancelled_progress {
+            return progress;
+        }
+        if self.total_weight > 0.0 {
+            return (1.0 - self.pending_weight() / self.total_weight).clamp(0.0, 1.0);
+        }
+        let remain = self.count.load(Ordering::Relaxed);
+        if self.total > 0 {
+            (self.total - remain) as f32 / self.total as f32
+        } else {
+            1.0
+        }
+    }
+
+    /// Total weight of all the assets and tasks submitted.
+    ///
+    /// See [`enqueue_weighted()`] and [`set_auto_weight()`].
+    ///
+    /// [`enqueue_weighted()`]: Loader::enqueue_weighted
+    /// [`set_auto_weight()`]: Loader::set_auto_weight
+    pub fn total_weight(&self) -> f32 {
+        self.total_weight
+    }
+
+    /// Total weight of the assets and tasks not yet completed.
+    pub fn pending_weight(&self) -> f32 {
+        self.pending_weights.lock().values().sum()
+    }
+
+    /// Number of bytes loaded for all the asset files of the batch.
+    ///
+    /// This requires the asset server to read its assets through a [`ProgressAssetIo`], and
+    /// is otherwise always zero. The bytes of an asset file count as loaded only once the entire
+    /// file was read, so this advances file by file. Asset files already read before the batch
+    /// was submitted, for example by another batch, count as loaded.
+    ///
+    /// [`ProgressAssetIo`]: crate::ProgressAssetIo
+    pub fn bytes_loaded(&self) -> u64 {
+        self.bytes.values().map(|count| count.loaded).sum()
+    }
+
+    /// Total size in bytes of all the asset files of the batch.
+    ///
+    /// The size of asset files is read from the asset folder when the batch starts loading, or
+    /// when they're appended, if found there, and otherwise is only known once the file was read.
+    /// Like [`bytes_loaded()`], this requires the asset server to read its assets through a
+    /// [`ProgressAssetIo`].
+    ///
+    /// [`bytes_loaded()`]: Loader::bytes_loaded
+    /// [`ProgressAssetIo`]: crate::ProgressAssetIo
+    pub fn bytes_total(&self) -> u64 {
+        self.bytes.values().filter_map(|count| count.total).sum()
+    }
+
+    /// Return loading progress measured in bytes, in \[0:1\].
+    ///
+    /// This is the ratio of [`bytes_loaded()`] to [`bytes_total()`], which like the former
+    /// advances file by file, each file counting for its size. Tasks don't count toward this
+    /// progress. Until the size of every asset file of the batch is known, for example because
+    /// the asset server doesn't use a [`ProgressAssetIo`] or doesn't read from the asset folder,
+    /// this falls back to [`progress()`].
+    ///
+    /// [`bytes_loaded()`]: Loader::bytes_loaded
+    /// [`bytes_total()`]: Loader::bytes_total
+    /// [`ProgressAssetIo`]: crate::ProgressAssetIo
+    /// [`progress()`]: Loader::progress
+    pub fn progress_bytes(&self) -> f32 {
+        let all_sized = self.bytes.values().all(|count| count.total.is_some());
+        let total = self.bytes_total();
+        if all_sized && total > 0 {
+            (self.bytes_loaded() as f64 / total as f64).clamp(0.0, 1.0) as f32
+        } else {
+            self.progress()
+        }
+    }
+
+    /// Time elapsed since the batch was submitted.
+    ///
+    /// This stops once the batch completes, and is zero before the batch is submitted.
+    pub fn elapsed(&self) -> Duration {
+        match self.submit_time {
+            Some(submit_time) => self
+                .end_time
+                .lock()
+                .unwrap_or_else(Instant::now)
+                .saturating_duration_since(submit_time),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Time spent loading since the batch was submitted up to `now`, excluding the time paused.
+    fn active_elapsed(&self, now: Instant) -> Duration {
+        let submit_time = match self.submit_time {
+            Some(submit_time) => submit_time,
+            None => return Duration::ZERO,
+        };
+        let end = self.end_time.lock().unwrap_or(now);
+        let paused = self.paused_for
+            + self
+                .paused_since
+                .map_or(Duration::ZERO, |since| end.saturating_duration_since(since));
+        end.saturating_duration_since(submit_time)
+            .saturating_sub(paused)
+    }
+
+    /// Time spent loading an asset or task from `started` up to `now`, excluding the time paused.
+    fn active_since(&self, started: Instant, now: Instant) -> Duration {
+        let paused: Duration = self
+            .pauses
+            .iter()
+            .copied()
+            .chain(self.paused_since.map(|since| (since, now)))
+            .map(|(from, to)| to.min(now).saturating_duration_since(from.max(started)))
+            .sum();
+        now.saturating_duration_since(started)
+            .saturating_sub(paused)
+    }
+
+    /// Pause the loader.
+    ///
+    /// While paused, the loader doesn't request any new asset from the asset server, including
+    /// assets waiting to be retried, and doesn't spawn any new task. The assets and tasks already
+    /// in flight are still tracked, and complete as usual, so the [`progress()`] keeps being
+    /// reported correctly. The time spent paused doesn't count toward the batch and asset
+    /// timeouts (see [`set_batch_timeout()`] and [`set_asset_timeout()`]) nor the
+    /// [`estimated_remaining()`] time.
+    ///
+    /// The loader can be paused in any state, including before a batch is submitted, and stays
+    /// paused until [`resume()`] is called, even if [`reset()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_bootloader::*;
+    /// let mut loader = Loader::new();
+    /// loader.enqueue("level2.gltf");
+    /// loader.submit();
+    /// // ...a cutscene starts, stop preloading...
+    /// loader.pause();
+    /// assert!(loader.is_paused());
+    /// // ...the cutscene ends...
+    /// loader.resume();
+    /// ```
+    ///
+    /// [`progress()`]: Loader::progress
+    /// [`set_batch_timeout()`]: Loader::set_batch_timeout
+    /// [`set_asset_timeout()`]: Loader::set_asset_timeout
+    /// [`estimated_remaining()`]: Loader::estimated_remaining
+    /// [`resume()`]: Loader::resume
+    /// [`reset()`]: Loader::reset
+    pub fn pause(&mut self) {
+        if self.paused_since.is_none() {
+            trace!("Loader paused");
+            self.paused_since = Some(Instant::now());
+        }
+    }
+
+    /// Resume the loader after it was paused with [`pause()`].
+    ///
+    /// [`pause()`]: Loader::pause
+    pub fn resume(&mut self) {
+        if let Some(since) = self.paused_since.take() {
+            trace!("Loader resumed");
+            let now = Instant::now();
+            self.paused_for += now.saturating_duration_since(since);
+            self.pauses.push((since, now));
+        }
+    }
+
+    /// Is the loader paused?
+    pub fn is_paused(&self) -> bool {
+        self.paused_since.is_some()
+    }
+
+    /// Estimated time remaining until all the assets and tasks of the batch complete.
+    ///
+    /// The estimate is based on the time taken to complete the assets and tasks already
+    /// completed, relative to their weight (see [`progress()`]), and is smoothed over time to
+    /// avoid sudden jumps. This returns `None` before the batch is submitted and until the
+    /// first asset or task completes, and zero once the batch completed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_bootloader::*;
+    /// # let loader = Loader::new();
+    /// if let Some(remaining) = loader.estimated_remaining() {
+    ///     println!("About {:.0} s remaining", remaining.as_secs_f32());
+    /// }
+    /// ```
+    ///
+    /// [`progress()`]: Loader::progress
+    pub fn estimated_remaining(&self) -> Option<Duration> {
+        match self.state() {
+            LoaderState::Ready | LoaderState::Cancelled => None,
+            LoaderState::Loading => self.eta.remaining(),
+            LoaderState::Done | LoaderState::Failed => Some(Duration::ZERO),
+        }
+    }
+
+    /// Time it took the asset or task with the given path or name to complete, from the moment
+    /// it was first requested from the asset server or spawned on its task pool, or `None` if it
+    /// did not complete successfully yet. For an asset retried after a failure, this includes
+    /// all its attempts.
+    pub fn finish_time(&self, path: &str) -> Option<Duration> {
+        self.finish_times.lock().get(path).copied()
+    }
+
+    /// Expected duration of the batch, if known from the [`LoadTimings`] of a previous run.
+    ///
+    /// See the [`LoadTimingsPlugin`].
+    ///
+    /// [`LoadTimingsPlugin`]: crate::LoadTimingsPlugin
+    pub fn expected_duration(&self) -> Option<Duration> {
+        self.expected_duration
+    }
+
+    /// Return loading progress based on the time elapsed, in \[0:1\].
+    ///
+    /// When the [`expected_duration()`] of the batch is known, this is the ratio of the
+    /// [`elapsed()`] time to that duration, which advances linearly with time. That ratio is
+    /// clamped to never fall behind the actual [`progress()`], nor reach 100% before the batch
+    /// completes. Otherwise this is equal to [`progress()`].
+    ///
+    /// [`expected_duration()`]: Loader::expected_duration
+    /// [`elapsed()`]: Loader::elapsed
+    /// [`progress()`]: Loader::progress
+    pub fn time_progress(&self) -> f32 {
+        let progress = self.progress();
+        match self.expected_duration {
+            Some(expected) if !expected.is_zero() && self.state() == LoaderState::Loading => {
+                let elapsed = self.active_elapsed(Instant::now());
+                let ratio = elapsed.as_secs_f32() / expected.as_secs_f32();
+                // Keep some margin, since the actual duration varies from run to run
+                ratio.min(0.99).max(progress)
+            }
+            _ => progress,
+        }
+    }
+
+    /// Use the timings of a previous run to weight the assets and tasks of the batch without
+    /// explicit weight, and to determine the expected duration of the batch.
+    ///
+    /// The expected duration is determined once per batch, after it's submitted. Assets appended
+    /// afterward are weighted on the next call, if the timings of the batch are known.
+    pub(crate) fn apply_timings(&mut self, timings: &LoadTimings, default_weight: Option<f32>) {
+        if self.state() != LoaderState::Loading
+            || (self.timings_applied && self.default_weighted.is_empty())
+        {
+            return;
+        }
+        let mut pending_weights = self.pending_weights.lock();
+        if !self.timings_applied {
+            self.timings_applied = true;
+            self.expected_duration = pending_weights
+                .keys()
+                .filter_map(|path| timings.get(path))
+                .max();
+        }
+        let paths = std::mem::take(&mut self.default_weighted);
+        if self.expected_duration.is_none() {
+            // No timing known for this batch, so keep the current weights
+            return;
+        }
+
+        let known: Vec<f32> = paths
+            .iter()
+            .filter_map(|path| timings.get(path))
+            .map(|duration| duration.as_secs_f32())
+            .collect();
+        let fallback = default_weight.unwrap_or_else(|| {
+            if known.is_empty() {
+                1.0
+            } else {
+                known.iter().sum::<f32>() / known.len() as f32
+            }
+        });
+        for path in paths {
+            let weight = timings
+                .get(&path)
+                .map_or(fallback, |duration| duration.as_secs_f32());
+            trace!("Timed weight: {} -> {}", path, weight);
+            if let Some(pending_weight) = pending_weights.get_mut(&path) {
+                self.total_weight += weight - *pending_weight;
+                *pending_weight = weight;
+            }
+        }
+        // Timings take precedence over file sizes
+        self.auto_weighted.clear();
+    }
+
+    /// Record the timings of the assets and tasks of the batch once it completed.
+    ///
+    /// Returns `true` if any timing changed.
+    pub(crate) fn record_timings(&mut self, timings: &mut LoadTimings) -> bool {
+        if self.timings_recorded || !matches!(self.state(), LoaderState::Done | LoaderState::Failed)
+        {
+            return false;
+        }
+        self.timings_recorded = true;
+        let mut changed = false;
+        for (path, duration) in self.finish_times.lock().iter() {
+            changed |= timings.insert(path.clone(), *duration);
+        }
+        changed
+    }
+
+    /// Send a [`LoaderCancelled`] event if the batch was cancelled since the last notification.
+    ///
+    /// This is called each update by the loader system, and by any system despawning the entity
+    /// of a cancelled loader before that, so the event is never lost.
+    pub(crate) fn notify_cancelled(
+        &self,
+        entity: Entity,
+        cancelled_events: &mut EventWriter<LoaderCancelled>,
+    ) {
+        let paths = std::mem::take(&mut *self.cancelled.lock());
+        if !paths.is_empty() {
+            cancelled_events.send(LoaderCancelled { entity, paths });
+        }
+    }
+
+    /// Smallest weight of the assets and tasks not yet completed, or zero if none.
+    pub(crate) fn min_pending_weight(&self) -> f32 {
+        self.pending_weights
+            .lock()
+            .values()
+            .copied()
+            .reduce(f32::min)
+            .unwrap_or(0.0)
+    }
+
+    /// Is the loader done loading the current asset batch?
+    ///
+    /// The batch is done once all assets finished loading, and all required assets loaded
+    /// successfully. Use [`has_failures()`] to check if some optional assets failed to load.
+    ///
+    /// [`has_failures()`]: Loader::has_failures
+    pub fn is_done(&self) -> bool {
+        *self.state.read() == LoaderState::Done
+    }
+
+    /// Did the current asset batch fail?
+    ///
+    /// The batch fails once all assets finished loading, if at least one asset enqueued with
+    /// [`enqueue()`] failed to load. Failures of optional assets enqueued with
+    /// [`enqueue_optional()`] don't fail the batch.
+    ///
+    /// [`enqueue()`]: Loader::enqueue
+    /// [`enqueue_optional()`]: Loader::enqueue_optional
+    pub fn is_batch_failed(&self) -> bool {
+        *self.state.read() == LoaderState::Failed
+    }
+
+    /// Check if the asset with the given path was enqueued as optional.
+    pub fn is_optional(&self, path: &str) -> bool {
+        self.optional.contains(path)
+    }
+
+    /// Check if the asset with the given path was loaded already.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy_bootloader::*;
+    /// # let mut loader = Loader::new();
+    /// if loader.is_loaded("image.png") {
+    ///     let handle = loader.take("image.png").unwrap();
+    /// }
+    /// ```
+    pub fn is_loaded(&self, path: &str) -> bool {
+        self.complete_queue.lock().contains_key(path)
+    }
+
+    /// Check if the asset with the given path failed to load.
+    pub fn is_failed(&self, path: &str) -> bool {
+        self.failed_queue.lock().contains_key(path)
+    }
+
+    /// Did any asset of the current batch fail to load?
+    ///
+    /// This includes optional assets, whose failure doesn't fail the batch.
+    pub fn has_failures(&self) -> bool {
+        !self.failed_queue.lock().is_empty()
+    }
+
+    /// Get the paths of all the assets of the current batch which failed to load.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_bootloader::*;
+    /// # let loader = Loader::new();
+    /// for path in loader.failed_paths() {
+    ///     error!("Failed to load asset: {}", path);
+    /// }
+    /// ```
+    pub fn failed_paths(&self) -> Vec<String> {
+        self.failed_queue.lock().keys().cloned().collect()
+    }
+
+    /// Get the reason why the asset with the given path failed to load, if it did.
+    pub fn failure_reason(&self, path: &str) -> Option<FailureReason> {
+        self.failed_queue.lock().get(path).copied()
+    }
+
+    /// Take the asset with the given path, if found and loaded, and remove its handle from the loader.
+    /// After this, the loader will forget about that asset and not keep it loaded anymore.
+    ///
+    /// This method returns an untyped handle because the [`Loader`] needs to be able to store internally
+    /// an heterogeneous collection of assets. Cast the handle to the expected type if needed, or use
+    /// [`take_typed()`] to also check the asset type:
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_bootloader::*;
+    /// # let mut loader = Loader::new();
+    /// if let Ok(handle) = loader.take("image.png") {
+    ///     let image_handle = handle.typed::<Image>();
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TakeError::Failed`] if the asset failed to load, [`TakeError::Pending`] if it
+    /// did not finish loading yet, and [`TakeError::NotFound`] if the path is unknown to the loader.
+    ///
+    /// [`take_typed()`]: Loader::take_typed
+    pub fn take(&mut self, path: &str) -> Result<HandleUntyped, TakeError> {
+        if let Some(handle) = self.complete_queue.lock().remove(path) {
+            self.taken.insert(path.to_owned());
+            return Ok(handle);
+        }
+        Err(self.missing_error(path))
+    }
+
+    /// Take the asset with the given path, if found and loaded, and remove its handle from the loader.
+    /// After this, the loader will forget about that asset and not keep it loaded anymore.
+    ///
+    /// Unlike [`take()`], this method checks that the asset is of the requested type `T` by looking it
+    /// up in the [`Assets<T>`] storage, which indirectly validates the asset's [`TypeUuid`]. If the asset
+    /// is of another type, an error is returned and the asset is left in the loader.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_bootloader::*;
+    /// fn update(mut query: Query<&mut Loader>, images: Res<Assets<Image>>) {
+    ///     let mut loader = query.single_mut();
+    ///     if loader.is_done() {
+    ///         match loader.take_typed::<Image>("image.png", &images) {
+    ///             Ok(image_handle) => { /* ... */ }
+    ///             Err(err) => error!("Failed to get image: {}", err),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TakeError::TypeMismatch`] if the asset is not of type `T`, or any of the errors
+    /// returned by [`take()`].
+    ///
+    /// [`take()`]: Loader::take
+    /// [`TypeUuid`]: bevy::reflect::TypeUuid
+    pub fn take_typed<T: Asset>(
+        &mut self,
+        path: &str,
+        assets: &Assets<T>,
+    ) -> Result<Handle<T>, TakeError> {
+        let mut complete_queue = self.complete_queue.lock();
+        match complete_queue.get(path) {
+            Some(handle) if assets.contains(handle.id) => {
+                self.taken.insert(path.to_owned());
+                Ok(complete_queue.remove(path).unwrap().typed::<T>())
+            }
+            Some(_) => Err(TakeError::TypeMismatch(TypeMismatch::of::<T>())),
+            None => {
+                drop(complete_queue);
+                Err(self.missing_error(path))
+            }
+        }
+    }
+
+    /// Get a new strong handle to the asset with the given path, if found and loaded. The loader
+    /// keeps its own handle, and the asset stays loaded.
+    ///
+    /// Like [`take_typed()`], this method checks that the asset is of the requested type `T` by
+    /// looking it up in the [`Assets<T>`] storage.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`take_typed()`].
+    ///
+    /// [`take_typed()`]: Loader::take_typed
+    pub fn get_typed<T: Asset>(
+        &self,
+        path: &str,
+        assets: &Assets<T>,
+    ) -> Result<Handle<T>, TakeError> {
+        let complete_queue = self.complete_queue.lock();
+        match complete_queue.get(path) {
+            Some(handle) if assets.contains(handle.id) => Ok(handle.clone().typed::<T>()),
+            Some(_) => Err(TakeError::TypeMismatch(TypeMismatch::of::<T>())),
+            None => {
+                drop(complete_queue);
+                Err(self.missing_error(path))
+            }
+        }
+    }
+
+    /// Take all the loaded assets, and remove their handles from the loader.
+    /// After this, the loader will forget about those assets and not keep them loaded anymore.
+    ///
+    /// Assets which failed to load or are still pending are not affected.
+    pub fn take_all(&mut self) -> HashMap<String, HandleUntyped> {
+        let handles = std::mem::take(&mut *self.complete_queue.lock());
+        self.taken.extend(handles.keys().cloned());
+        handles
+    }
+
+    /// Check if the task with the given name completed, and its output is available.
+    pub fn is_task_done(&self, name: &str) -> bool {
+        self.task_outputs.lock().contains_key(name)
+    }
+
+    /// Take the output of the completed task with the given name, and remove it from the loader.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TakeError::TaskOutputMismatch`] if the output is not of type `T`, in which case
+    /// the output is left in the loader. Otherwise returns [`TakeError::Failed`] if the task timed
+    /// out, [`TakeError::Pending`] if it did not complete yet, and [`TakeError::NotFound`] if the
+    /// name is unknown to the loader or the output was already taken.
+    pub fn take_task<T: Send + 'static>(&mut self, name: &str) -> Result<T, TakeError> {
+        let mut task_outputs = self.task_outputs.lock();
+        match task_outputs.remove(name) {
+            Some(output) => match output.downcast::<T>() {
+                Ok(output) => {
+                    self.taken.insert(name.to_owned());
+                    Ok(*output)
+                }
+                Err(output) => {
+                    task_outputs.insert(name.to_owned(), output);
+                    Err(TakeError::TaskOutputMismatch(std::any::type_name::<T>()))
+                }
+            },
+            None => {
+                drop(task_outputs);
+                Err(self.missing_error(name))
+            }
+        }
+    }
+
+    /// Get the error describing why an asset which is not loaded cannot be taken.
+    fn missing_error(&self, path: &str) -> TakeError {
+        if let Some(reason) = self.failed_queue.lock().get(path) {
+            return TakeError::Failed(*reason);
+        }
+        if self.request_queue.lock().iter().any(|p| p == path)
+            || self.work_queue.lock().iter().any(|w| w.path == path)
+            || self.retry_queue.lock().iter().any(|r| r.path == path)
+            || self.task_queue.lock().iter().any(|t| t.name == path)
+            || self.running_tasks.lock().iter().any(|t| t.name == path)
+        {
+            return TakeError::Pending;
+        }
+        TakeError::NotFound
+    }
+
+    /// Record the completion of a single asset, whether successful or not.
+    fn finish(&self, path: String, result: Result<HandleUntyped, FailureReason>) {
+        {
+            let mut pending_weights = self.pending_weights.lock();
+            pending_weights.remove(&path);
+            if self.count_dependencies {
+                let prefix = dependency_prefix(&path);
+                pending_weights.retain(|key, _| !key.starts_with(&prefix));
+            }
+        }
+        match result {
+            Ok(handle) => {
+                trace!("Asset finished loading: {} {:?}", path, handle);
+                self.loaded.fetch_add(1, Ordering::AcqRel);
+                self.complete_queue.lock().insert(path, handle);
+            }
+            Err(reason) => {
+                warn!("Asset failed to load: {} ({})", path, reason);
+                self.failed_queue.lock().insert(path, reason);
+            }
+        }
+        self.complete_one();
+    }
+
+    /// Record the completion of a task.
+    fn finish_task(&self, name: String, output: TaskOutput) {
+        trace!("Task completed: {}", name);
+        self.pending_weights.lock().remove(&name);
+        self.task_outputs.lock().insert(name, output);
+        self.complete_one();
+    }
+
+    /// Record the time an asset or task took to complete since it started.
+    fn record_finish_time(&self, path: &str, started: Instant, now: Instant) {
+        self.finish_times
+            .lock()
+            .insert(path.to_string(), now.saturating_duration_since(started));
+    }
+
+    /// Decrement the number of pending items, and complete the batch once none remains.
+    fn complete_one(&self) {
+        if self.count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            // Last asset finished, all done
+            *self.end_time.lock() = Some(Instant::now());
+            let required_failed = self
+                .failed_queue
+                .lock()
+                .keys()
+                .any(|path| !self.optional.contains(path));
+            *self.state.write() = if required_failed {
+                LoaderState::Failed
+            } else {
+                LoaderState::Done
+            };
+        }
+    }
+
+    /// Record a failed attempt to load an asset, and either schedule a retry or mark the asset
+    /// as failed depending on the retry policy.
+    fn fail(
+        &self,
+        path: String,
+        handle: Option<HandleUntyped>,
+        attempt: u32,
+        started: Instant,
+        reason: FailureReason,
+        now: Instant,
+    ) {
+        if let Some(delay) = self.retry_policy.delay(attempt) {
+            debug!(
+                "Asset failed to load: {} ({}); retrying after {:?} (attempt {}/{})",
+                path,
+                reason,
+                delay,
+                attempt + 1,
+                self.retry_policy.max_attempts
+            );
+            let due = match delay {
+                RetryDelay::Frames(frames) => Due::Frame(self.frame + frames as u64),
+                RetryDelay::Seconds(seconds) => {
+                    // Large or invalid delays, notably from an exponential backoff, are clamped
+                    // to keep the conversion and the addition from panicking
+                    let seconds = if seconds.is_nan() {
+                        0.0
+                    } else {
+                        seconds.clamp(0.0, MAX_RETRY_DELAY_SECONDS)
+                    };
+                    let delay = Duration::from_secs_f32(seconds);
+                    Due::Time(now.checked_add(delay).unwrap_or(now))
+                }
+            };
+            self.retry_queue.lock().push(Retry {
+                path,
+                attempt,
+                due,
+                started,
+                handle,
+            });
+        } else {
+            self.finish(path, Err(reason));
+        }
+    }
+
+    /// Start loading an asset via the asset server.
+    fn load(
+        &self,
+        asset_server: &AssetServer,
+        path: String,
+        attempt: u32,
+        started: Instant,
+        now: Instant,
+    ) {
+        let handle = asset_server.load_untyped(&path[..]);
+        let load_state = asset_server.get_load_state(&handle);
+        // On retry, the asset server keeps reporting the failure of the previous attempt until
+        // the new one starts; always wait for it instead of failing again right away.
+        if attempt > 1 {
+            trace!("Retry loading asset: {} -> {:?}", path, &handle);
+            let started_again = matches!(load_state, LoadState::Loading | LoadState::Loaded);
+            self.work_queue.lock().push(Work {
+                path,
+                handle,
+                attempt,
+                started,
+                dependencies: None,
+                retry_time: if started_again { None } else { Some(now) },
+            });
+            return;
+        }
+        // Enqueue unless failed; either the resource is already loading (need to wait), or is
+        // loaded (need to check its dependencies, if any), or failed (retry or give up).
+        match load_state {
+            LoadState::NotLoaded | LoadState::Loading | LoadState::Loaded => {
+                trace!("Start loading asset: {} -> {:?}", path, &handle);
+                self.work_queue.lock().push(Work {
+                    path,
+                    handle,
+                    attempt,
+                    started,
+                    dependencies: None,
+                    retry_time: None,
+                });
+            }
+            LoadState::Failed => {
+                let reason = FailureReason::LoadFailed;
+                self.fail(path, Some(handle), attempt, started, reason, now);
+            }
+            LoadState::Unloaded => {
+                let reason = FailureReason::Unloaded;
+                self.fail(path, Some(handle), attempt, started, reason, now);
+            }
+        }
+    }
+
+    /// Mark as failed all pending assets whose deadline passed.
+    fn check_timeouts(&self, now: Instant) {
+        let batch_expired = match (self.batch_timeout, self.submit_time) {
+            (Some(timeout), Some(_)) => self.active_elapsed(now) >= timeout,
+            _ => false,
+        };
+        let timed_out: Vec<String> = if batch_expired {
+            let mut paths: Vec<String> = std::mem::take(&mut *self.request_queue.lock());
+            paths.extend(self.work_queue.lock().drain(..).map(|w| w.path));
+            paths.extend(self.retry_queue.lock().drain(..).map(|r| r.path));
+            paths.extend(self.task_queue.lock().drain(..).map(|t| t.name));
+            // Dropping the running tasks cancels them
+            paths.extend(self.running_tasks.lock().drain(..).map(|t| t.name));
+            paths
+        } else if let Some(timeout) = self.asset_timeout {
+            let expired = |started: Instant| self.active_since(started, now) >= timeout;
+            let mut paths = vec![];
+            self.work_queue.lock().retain(|w| {
+                if expired(w.started) {
+                    paths.push(w.path.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            self.retry_queue.lock().retain(|r| {
+                if expired(r.started) {
+                    paths.push(r.path.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            self.running_tasks.lock().retain(|t| {
+                if expired(t.started) {
+                    paths.push(t.name.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            paths
+        } else {
+            vec![]
+        };
+        if timed_out.is_empty() {
+            return;
+        }
+        warn!("Assets timed out: {:?}", timed_out);
+        for path in &timed_out {
+            self.finish(path.clone(), Err(FailureReason::TimedOut));
+        }
+        self.timed_out.lock().extend(timed_out);
+    }
+
+    /// Resolve the dependencies of the loaded assets not yet resolved.
+    ///
+    /// The `resolve` function returns the dependencies of an asset, or `None` if the asset is
+    /// not of the type it handles.
+    pub(crate) fn resolve_dependencies(
+        &mut self,
+        asset_server: &AssetServer,
+        resolve: impl Fn(HandleId) -> Option<Vec<HandleId>>,
+    ) {
+        let mut work_queue = self.work_queue.lock();
+        for work in work_queue.iter_mut() {
+            if work.dependencies.is_some()
+                || asset_server.get_load_state(&work.handle) != LoadState::Loaded
+            {
+                continue;
+            }
+            if let Some(dependencies) = resolve(work.handle.id) {
+                // Only assets loaded from a path can be waited for; others are already in memory
+                let mut dependencies: Vec<HandleId> = dependencies
+                    .into_iter()
+                    .filter(|id| matches!(id, HandleId::AssetPathId(_)))
+                    .collect();
+                dependencies.sort_unstable();
+                dependencies.dedup();
+                trace!(
+                    "Resolved {} dependencies for asset: {}",
+                    dependencies.len(),
+                    work.path
+                );
+                if self.count_dependencies && !dependencies.is_empty() {
+                    let mut pending_weights = self.pending_weights.lock();
+                    for id in &dependencies {
+                        pending_weights.insert(dependency_key(&work.path, id), 1.0);
+                    }
+                    self.total_weight += dependencies.len() as f32;
+                }
+                work.dependencies = Some(dependencies);
+            }
+        }
+    }
+
+    /// Mark as completed in the progress the dependencies which finished loading.
+    fn update_dependencies(
+        &self,
+        asset_server: &AssetServer,
+        path: &str,
+        dependencies: &[HandleId],
+    ) {
+        if !self.count_dependencies {
+            return;
+        }
+        let mut pending_weights = self.pending_weights.lock();
+        for id in dependencies {
+            if !matches!(
+                asset_server.get_load_state(*id),
+                LoadState::NotLoaded | LoadState::Loading
+            ) {
+                pending_weights.remove(&dependency_key(path, id));
+            }
+        }
+    }
+
+    /// Determine the weight of the assets without explicit weight from their file size, reading
+    /// the files from the given asset folder if any.
+    fn resolve_auto_weights(&mut self, asset_folder: Option<&Path>) {
+        let paths = std::mem::take(&mut self.auto_weighted);
+        let sizes: Vec<(String, Option<f32>)> = paths
+            .into_iter()
+            .map(|path| {
+                let size = asset_folder
+                    .and_then(|folder| std::fs::metadata(folder.join(&path)).ok())
+                    .map(|metadata| metadata.len() as f32);
+                (path, size)
+            })
+            .collect();
+        let known: Vec<f32> = sizes.iter().filter_map(|(_, size)| *size).collect();
+        let fallback = if known.is_empty() {
+            1.0
+        } else {
+            known.iter().sum::<f32>() / known.len() as f32
+        };
+        let mut pending_weights = self.pending_weights.lock();
+        for (path, size) in sizes {
+            trace!("Auto weight: {} -> {:?}", path, size);
+            if let Some(weight) = pending_weights.get_mut(&path) {
+                let size = size.unwrap_or(fallback);
+                self.total_weight += size - *weight;
+                *weight = size;
+            }
+        }
+    }
+
+    /// Update the number of bytes loaded from the given stats, reading the size of the asset
+    /// files from the given asset folder, if any, on the first update after they're added to the
+    /// batch.
+    fn update_bytes(&mut self, stats: &AssetIoStats, asset_folder: Option<&Path>) {
+        // Failed files are never read, so don't count toward the remaining bytes
+        let failed: HashSet<PathBuf> = self
+            .failed_queue
+            .lock()
+            .keys()
+            .map(|path| file_path(path))
+            .collect();
+        for (path, count) in self.bytes.iter_mut() {
+            if let Some(bytes) = stats.bytes_read(path) {
+                count.loaded = bytes;
+                count.total = Some(bytes);
+            } else if failed.contains(path) {
+                count.total = Some(count.loaded);
+            } else if !count.looked_up {
+                count.total = asset_folder
+                    .and_then(|folder| std::fs::metadata(folder.join(path)).ok())
+                    .map(|metadata| metadata.len());
+            }
+            count.looked_up = true;
+        }
+    }
+
+    fn tick(
+        &mut self,
+        asset_server: &AssetServer,
+        compute_pool: &TaskPool,
+        io_pool: &TaskPool,
+        now: Instant,
+    ) {
+        self.frame += 1;
+
+        // Poll running tasks and remove completed ones
+        {
+            let mut running_tasks = self.running_tasks.lock();
+            let mut i = 0;
+            while i < running_tasks.len() {
+                match future::block_on(future::poll_once(&mut running_tasks[i].task)) {
+                    Some(output) => {
+                        let task = running_tasks.remove(i);
+                        self.record_finish_time(&task.name, task.started, now);
+                        self.finish_task(task.name, output);
+                    }
+                    None => i += 1,
+                }
             }
         }
 
-        // Swap request queue atomically
-        let mut request_queue: Vec<String> = {
-            let mut request_queue = self.request_queue.lock();
-            std::mem::replace(&mut request_queue, vec![])
-        };
-        // Drain request queue and enqueue new asset loading requests
-        for path in request_queue.drain(..) {
-            let handle = asset_server.load_untyped(&path[..]);
-            // Only enqueue if not loaded; otherwise either the resource is already loading
-            // (need to wait), is loaded (nothing to do), or failed (no point retrying).
-            match asset_server.get_load_state(&handle) {
-                bevy::asset::LoadState::NotLoaded | bevy::asset::LoadState::Loading => {
-                    trace!("Start loading asset: {} -> {:?}", path, &handle);
-                    self.work_queue.lock().push((path, handle));
+        // Check pending asset loading requests and remove completed ones
+        {
+            let mut work_queue = self.work_queue.lock();
+            // TODO - Vec::drain_filter()
+            let mut i = 0;
+            while i < work_queue.len() {
+                let work = &mut work_queue[i];
+                let load_state = asset_server.get_load_state(&work.handle);
+                if let Some(retry_time) = work.retry_time {
+                    match load_state {
+                        LoadState::Loading | LoadState::Loaded => work.retry_time = None,
+                        // Stale state of the previous attempt, unless the new one already failed
+                        // between two frames and was never observed loading
+                        _ if now.saturating_duration_since(retry_time)
+                            < self.retry_policy.start_timeout =>
+                        {
+                            i += 1;
+                            continue;
+                        }
+                        _ => {}
+                    }
                 }
-                bevy::asset::LoadState::Loaded
-                | bevy::asset::LoadState::Failed
-                | bevy::asset::LoadState::Unloaded => {
-                    trace!("Asset: {} -> {:?}", path, &handle);
-                    self.count.fetch_sub(1, Ordering::Release);
+                let load_state = match load_state {
+                    LoadState::Loaded => match &work.dependencies {
+                        Some(dependencies) => {
+                            self.update_dependencies(asset_server, &work.path, dependencies);
+                            asset_server.get_group_load_state(dependencies.iter().copied())
+                        }
+                        None => LoadState::Loaded,
+                    },
+                    load_state => load_state,
+                };
+                let reason = match load_state {
+                    LoadState::Loaded => None,
+                    LoadState::Failed => Some(FailureReason::LoadFailed),
+                    LoadState::Unloaded => Some(FailureReason::Unloaded),
+                    LoadState::NotLoaded | LoadState::Loading => {
+                        i += 1;
+                        continue;
+                    }
+                };
+                let work = work_queue.remove(i);
+                match reason {
+                    None => {
+                        self.record_finish_time(&work.path, work.started, now);
+                        self.finish(work.path, Ok(work.handle));
+                    }
+                    Some(reason) => self.fail(
+                        work.path,
+                        Some(work.handle),
+                        work.attempt,
+                        work.started,
+                        reason,
+                        now,
+                    ),
                 }
             }
         }
+
+        // Fail all assets whose deadline passed
+        self.check_timeouts(now);
+
+        // While paused, only track the assets and tasks already in flight
+        if self.is_paused() {
+            return;
+        }
+
+        // Extract the failed assets due for retry
+        let retries: Vec<Retry> = {
+            let frame = self.frame;
+            let mut retry_queue = self.retry_queue.lock();
+            let (due, waiting) = std::mem::take(&mut *retry_queue)
+                .into_iter()
+                .partition(|retry| match retry.due {
+                    Due::Frame(due) => frame >= due,
+                    Due::Time(due) => now >= due,
+                });
+            *retry_queue = waiting;
+            due
+        };
+        for retry in retries {
+            self.load(
+                asset_server,
+                retry.path,
+                retry.attempt + 1,
+                retry.started,
+                now,
+            );
+            // Only release the previous handle once the new attempt holds the asset
+            drop(retry.handle);
+        }
+
+        // Only start the assets and tasks of a submitted batch, which counts them on submit
+        if self.state() == LoaderState::Loading {
+            // Swap request queue atomically
+            let mut request_queue: Vec<String> = std::mem::take(&mut *self.request_queue.lock());
+            // Drain request queue and enqueue new asset loading requests
+            for path in request_queue.drain(..) {
+                self.load(asset_server, path, 1, now, now);
+            }
+
+            // Spawn enqueued tasks on their task pool
+            let task_queue: Vec<PendingTask> = std::mem::take(&mut *self.task_queue.lock());
+            for pending in task_queue {
+                let pool = match pending.pool {
+                    TaskPoolKind::Compute => compute_pool,
+                    TaskPoolKind::Io => io_pool,
+                };
+                trace!("Spawn task: {} ({:?})", pending.name, pending.pool);
+                self.running_tasks.lock().push(RunningTask {
+                    name: pending.name,
+                    task: pool.spawn(pending.future),
+                    started: now,
+                });
+            }
+        }
+
+        self.update_eta(now);
+    }
+
+    /// Update the estimated remaining time of the batch.
+    fn update_eta(&mut self, now: Instant) {
+        match (self.state(), self.submit_time) {
+            (LoaderState::Loading, Some(_)) => {
+                let progress = self.progress();
+                let elapsed = self.active_elapsed(now);
+                self.eta.update(progress, elapsed);
+            }
+            (LoaderState::Done | LoaderState::Failed, _) => self.eta.complete(),
+            _ => {}
+        }
+    }
+}
+
+/// Handle the result of one of the infallible enqueue methods, ignoring a duplicate `kind` named
+/// `name` with a warning and panicking on any other error.
+fn expect_enqueued(result: Result<(), LoaderError>, kind: &str, name: &str) {
+    match result {
+        Ok(()) => {}
+        Err(LoaderError::DuplicatePath(_)) => {
+            warn!(
+                "Ignoring duplicate {} '{}' already part of the batch",
+                kind, name
+            );
+        }
+        Err(err) => panic!("Failed to enqueue {} '{}': {}", kind, name, err),
     }
 }
 
-fn tick_loaders(asset_server: Res<AssetServer>, mut query: Query<(&mut Loader,)>) {
-    let asset_server: &AssetServer = &*asset_server;
-    for (mut loader,) in query.iter_mut() {
-        loader.tick(asset_server);
+/// Path of the file of an asset, without its label if any.
+fn file_path(path: &str) -> PathBuf {
+    AssetPath::from(path).path().to_path_buf()
+}
+
+/// Key of a dependency of an asset in the pending weights.
+fn dependency_key(path: &str, id: &HandleId) -> String {
+    format!("{}{:?}", dependency_prefix(path), id)
+}
+
+/// Prefix of the keys of all the dependencies of an asset in the pending weights.
+fn dependency_prefix(path: &str) -> String {
+    // Asset paths can't contain a nul character, so this can't collide with another asset
+    format!("{}\u{0}", path)
+}
+
+/// Folder of the asset files read by the default file-based asset I/O.
+#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+fn asset_folder(settings: Option<&AssetServerSettings>) -> Option<PathBuf> {
+    let folder = settings.map_or("assets", |settings| &settings.asset_folder[..]);
+    Some(FileAssetIo::get_root_path().join(folder))
+}
+
+/// Folder of the asset files, not available on platforms without a file-based asset I/O.
+#[cfg(any(target_arch = "wasm32", target_os = "android"))]
+fn asset_folder(_settings: Option<&AssetServerSettings>) -> Option<PathBuf> {
+    None
+}
+
+#[allow(clippy::too_many_arguments)]
+fn tick_loaders(
+    asset_server: Res<AssetServer>,
+    compute_pool: Res<AsyncComputeTaskPool>,
+    io_pool: Res<IoTaskPool>,
+    asset_server_settings: Option<Res<AssetServerSettings>>,
+    asset_io_stats: Option<Res<AssetIoStats>>,
+    mut query: Query<(Entity, &mut Loader)>,
+    mut timed_out_events: EventWriter<LoaderTimedOut>,
+    mut cancelled_events: EventWriter<LoaderCancelled>,
+) {
+    let asset_server: &AssetServer = &asset_server;
+    let now = Instant::now();
+    // Only file sizes need the asset folder
+    let needs_folder = asset_io_stats.is_some()
+        || query
+            .iter()
+            .any(|(_, loader)| !loader.auto_weighted.is_empty());
+    let asset_folder = if needs_folder {
+        asset_folder(asset_server_settings.as_deref())
+    } else {
+        None
+    };
+    for (entity, mut loader) in query.iter_mut() {
+        // Resolve automatic weights before the first update of the batch
+        if !loader.auto_weighted.is_empty() && loader.state() == LoaderState::Loading {
+            loader.resolve_auto_weights(asset_folder.as_deref());
+        }
+        loader.tick(asset_server, &compute_pool, &io_pool, now);
+        if let Some(stats) = &asset_io_stats {
+            if loader.state() != LoaderState::Ready {
+                loader.update_bytes(stats, asset_folder.as_deref());
+            }
+        }
+        let paths = std::mem::take(&mut *loader.timed_out.lock());
+        if !paths.is_empty() {
+            timed_out_events.send(LoaderTimedOut { entity, paths });
+        }
+        loader.notify_cancelled(entity, &mut cancelled_events);
     }
 }
 
 /// Plugin to initialize the use of the [`Loader`] component and update all instances each frame.
 ///
+/// This also registers the [`LoaderTimedOut`] and [`LoaderCancelled`] events.
+///
 /// The [`Loader`] instances are updated in the [`LoaderStage::UpdateLoaders`] stage, which is
 /// inserted after the internal [`AssetStage::LoadAssets`] one.
 ///
@@ -299,6 +2612,14 @@ pub enum LoaderStage {
     UpdateLoaders,
 }
 
+/// Label of the loader systems, for ordering other systems relative to them.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, SystemLabel)]
+pub enum LoaderSystem {
+    /// The system updating all the [`Loader`] instances, in the [`LoaderStage::UpdateLoaders`]
+    /// stage.
+    TickLoaders,
+}
+
 impl Plugin for LoaderPlugin {
     fn build(&self, app: &mut App) {
         app.add_stage_after(
@@ -306,13 +2627,20 @@ impl Plugin for LoaderPlugin {
             LoaderStage::UpdateLoaders,
             SystemStage::single_threaded(),
         )
-        .add_system_to_stage(LoaderStage::UpdateLoaders, tick_loaders);
+        .add_event::<LoaderTimedOut>()
+        .add_event::<LoaderCancelled>()
+        .add_system_to_stage(
+            LoaderStage::UpdateLoaders,
+            tick_loaders.label(LoaderSystem::TickLoaders),
+        );
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{test_app, update_until};
+    use bevy::{app::Events, asset::HandleId, reflect::TypeUuid};
 
     #[test]
     fn empty() {
@@ -320,6 +2648,7 @@ mod tests {
         loader.submit();
         assert!(loader.is_empty());
         assert_eq!(loader.pending_count(), 0);
+        assert!(loader.is_done());
     }
 
     #[test]
@@ -332,4 +2661,856 @@ mod tests {
         //let asset_server = AssetServer::new(asset_io, task_queue);
         //loader.work(&asset_server);
     }
+
+    #[test]
+    fn failures() {
+        let mut loader = Loader::new();
+        loader.enqueue("ok");
+        loader.enqueue("ko");
+        loader.enqueue("pending");
+        loader.submit();
+        assert!(!loader.has_failures());
+
+        // Simulate the request being processed by the asset server
+        let paths = std::mem::take(&mut *loader.request_queue.lock());
+        for path in paths {
+            loader.work_queue.lock().push(Work {
+                path,
+                handle: HandleUntyped::weak(HandleId::default::<Image>()),
+                attempt: 1,
+                started: Instant::now(),
+                dependencies: None,
+                retry_time: None,
+            });
+        }
+        let work = loader.work_queue.lock().remove(0);
+        loader.finish(work.path, Ok(work.handle));
+        let work = loader.work_queue.lock().remove(0);
+        loader.fail(
+            work.path,
+            Some(work.handle),
+            work.attempt,
+            work.started,
+            FailureReason::LoadFailed,
+            Instant::now(),
+        );
+
+        assert!(loader.has_failures());
+        assert_eq!(loader.failed_paths(), vec!["ko".to_string()]);
+        assert!(loader.is_failed("ko"));
+        assert!(!loader.is_failed("ok"));
+        assert_eq!(loader.failure_reason("ko"), Some(FailureReason::LoadFailed));
+        assert_eq!(loader.pending_count(), 1);
+        assert!(!loader.is_done());
+
+        assert!(loader.take("ok").is_ok());
+        assert_eq!(loader.take("ok"), Err(TakeError::NotFound));
+        assert_eq!(
+            loader.take("ko"),
+            Err(TakeError::Failed(FailureReason::LoadFailed))
+        );
+        assert_eq!(loader.take("pending"), Err(TakeError::Pending));
+        assert_eq!(loader.take("unknown"), Err(TakeError::NotFound));
+
+        let work = loader.work_queue.lock().remove(0);
+        loader.finish(work.path, Ok(work.handle));
+        assert!(!loader.is_done());
+        assert!(loader.is_batch_failed());
+
+        loader.reset();
+        assert!(!loader.has_failures());
+    }
+
+    #[test]
+    fn retry_policy() {
+        let policy = RetryPolicy::none();
+        assert_eq!(policy.delay(1), None);
+        assert_eq!(policy.start_timeout, Duration::from_secs(1));
+
+        let policy = RetryPolicy::fixed(3, RetryDelay::Frames(2));
+        assert_eq!(policy.delay(1), Some(RetryDelay::Frames(2)));
+        assert_eq!(policy.delay(2), Some(RetryDelay::Frames(2)));
+        assert_eq!(policy.delay(3), None);
+
+        let policy = RetryPolicy::exponential(4, RetryDelay::Seconds(0.5), 2.0);
+        assert_eq!(policy.delay(1), Some(RetryDelay::Seconds(0.5)));
+        assert_eq!(policy.delay(2), Some(RetryDelay::Seconds(1.0)));
+        assert_eq!(policy.delay(3), Some(RetryDelay::Seconds(2.0)));
+        assert_eq!(policy.delay(4), None);
+
+        // Overflowing delays are clamped instead of panicking
+        let mut loader = Loader::new();
+        loader.set_retry_policy(RetryPolicy::exponential(
+            100,
+            RetryDelay::Seconds(1.0),
+            1e30,
+        ));
+        loader.enqueue("flaky");
+        loader.submit();
+        let now = Instant::now();
+        loader.fail(
+            "flaky".to_string(),
+            None,
+            3,
+            now,
+            FailureReason::LoadFailed,
+            now,
+        );
+        match loader.retry_queue.lock()[0].due {
+            Due::Time(due) => {
+                assert_eq!(due - now, Duration::from_secs_f32(MAX_RETRY_DELAY_SECONDS))
+            }
+            Due::Frame(_) => panic!("unexpected frame delay"),
+        }
+        loader.set_retry_policy(RetryPolicy::fixed(2, RetryDelay::Seconds(f32::NAN)));
+        loader.fail(
+            "flaky".to_string(),
+            None,
+            1,
+            now,
+            FailureReason::LoadFailed,
+            now,
+        );
+        assert!(matches!(loader.retry_queue.lock()[1].due, Due::Time(due) if due == now));
+    }
+
+    #[test]
+    fn retry() {
+        let mut loader = Loader::new();
+        loader.set_retry_policy(RetryPolicy::fixed(2, RetryDelay::Frames(1)));
+        loader.enqueue("flaky");
+        loader.submit();
+
+        let now = Instant::now();
+        loader.fail(
+            "flaky".to_string(),
+            None,
+            1,
+            now,
+            FailureReason::LoadFailed,
+            now,
+        );
+        assert!(!loader.has_failures());
+        assert_eq!(loader.pending_count(), 1);
+        assert_eq!(loader.take("flaky"), Err(TakeError::Pending));
+
+        loader.fail(
+            "flaky".to_string(),
+            None,
+            2,
+            now,
+            FailureReason::LoadFailed,
+            now,
+        );
+        assert!(loader.is_failed("flaky"));
+        assert_eq!(loader.pending_count(), 0);
+        assert!(loader.is_batch_failed());
+    }
+
+    #[test]
+    fn retry_asset_server() {
+        let (mut app, io) = test_app();
+        app.add_plugin(LoaderPlugin);
+        io.add("flaky.test", "");
+        io.fail("flaky.test", 1);
+
+        let mut loader = Loader::new();
+        loader.set_retry_policy(RetryPolicy::fixed(2, RetryDelay::Frames(1)));
+        loader.enqueue("flaky.test");
+        loader.submit();
+        let entity = app.world.spawn().insert(loader).id();
+
+        // Hold the second attempt, which must not be mistaken for the failed first one
+        update_until(&mut app, |world| {
+            !world
+                .get::<Loader>(entity)
+                .unwrap()
+                .retry_queue
+                .lock()
+                .is_empty()
+        });
+        io.set_blocked("flaky.test", true);
+        for _ in 0..5 {
+            app.update();
+        }
+        let loader = app.world.get::<Loader>(entity).unwrap();
+        assert_eq!(loader.state(), LoaderState::Loading);
+        assert_eq!(loader.pending_count(), 1);
+        assert!(!loader.has_failures());
+
+        io.set_blocked("flaky.test", false);
+        update_until(&mut app, |world| {
+            world.get::<Loader>(entity).unwrap().is_done()
+        });
+        let mut loader = app.world.get_mut::<Loader>(entity).unwrap();
+        assert_eq!(loader.state(), LoaderState::Done);
+        assert!(!loader.has_failures());
+        assert!(loader.take("flaky.test").is_ok());
+        assert_eq!(io.reads("flaky.test"), 2);
+    }
+
+    #[test]
+    fn batch_timeout() {
+        let mut loader = Loader::new();
+        loader.set_batch_timeout(Some(Duration::from_secs(5)));
+        loader.enqueue("a");
+        loader.enqueue("b");
+        loader.submit();
+        let submit_time = loader.submit_time.unwrap();
+
+        loader.check_timeouts(submit_time + Duration::from_secs(4));
+        assert!(!loader.has_failures());
+        assert_eq!(loader.pending_count(), 2);
+
+        loader.check_timeouts(submit_time + Duration::from_secs(5));
+        assert_eq!(loader.failure_reason("a"), Some(FailureReason::TimedOut));
+        assert_eq!(loader.failure_reason("b"), Some(FailureReason::TimedOut));
+        assert_eq!(loader.pending_count(), 0);
+        assert!(loader.is_batch_failed());
+        assert_eq!(loader.timed_out.lock().len(), 2);
+    }
+
+    #[test]
+    fn asset_timeout_paused() {
+        let mut loader = Loader::new();
+        loader.set_asset_timeout(Some(Duration::from_secs(5)));
+        loader.enqueue("a");
+        loader.submit();
+        let started = loader.submit_time.unwrap();
+        loader.request_queue.lock().clear();
+        loader.work_queue.lock().push(Work {
+            path: "a".to_string(),
+            handle: HandleUntyped::weak(HandleId::default::<Image>()),
+            attempt: 1,
+            started,
+            dependencies: None,
+            retry_time: None,
+        });
+
+        // Paused from 2s to 12s, so only 4s of loading at 14s
+        let second = |secs| started + Duration::from_secs(secs);
+        loader.pauses.push((second(2), second(12)));
+        loader.check_timeouts(second(14));
+        assert!(!loader.has_failures());
+
+        // An ongoing pause doesn't count either
+        loader.paused_since = Some(second(14));
+        loader.check_timeouts(second(100));
+        assert!(!loader.has_failures());
+
+        loader.paused_since = None;
+        loader.check_timeouts(second(15));
+        assert_eq!(loader.failure_reason("a"), Some(FailureReason::TimedOut));
+        assert_eq!(loader.pending_count(), 0);
+    }
+
+    #[test]
+    fn asset_timeout() {
+        let (mut app, io) = test_app();
+        app.add_plugin(LoaderPlugin);
+        io.add("fast.test", "");
+        io.add("slow.test", "");
+        io.add("flaky.test", "");
+        io.set_blocked("slow.test", true);
+        io.fail("flaky.test", u32::MAX);
+
+        let mut loader = Loader::new();
+        loader.set_asset_timeout(Some(Duration::from_millis(200)));
+        loader.set_retry_policy(RetryPolicy::fixed(1000, RetryDelay::Seconds(0.02)));
+        loader.enqueue("fast.test");
+        loader.enqueue("slow.test");
+        loader.enqueue("flaky.test");
+        loader.submit();
+        let entity = app.world.spawn().insert(loader).id();
+
+        // Retries don't extend the deadline, which counts from the first request
+        update_until(&mut app, |world| {
+            world.get::<Loader>(entity).unwrap().is_batch_failed()
+        });
+        let mut events = app
+            .world
+            .get_resource_mut::<Events<LoaderTimedOut>>()
+            .unwrap();
+        let events: Vec<LoaderTimedOut> = events.drain().collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].entity, entity);
+        let mut paths = events[0].paths.clone();
+        paths.sort();
+        assert_eq!(paths, vec!["flaky.test", "slow.test"]);
+
+        let loader = app.world.get::<Loader>(entity).unwrap();
+        assert!(!loader.is_failed("fast.test"));
+        assert_eq!(
+            loader.failure_reason("slow.test"),
+            Some(FailureReason::TimedOut)
+        );
+        assert_eq!(
+            loader.failure_reason("flaky.test"),
+            Some(FailureReason::TimedOut)
+        );
+        assert!(loader.retry_queue.lock().is_empty());
+        let reads = io.reads("flaky.test");
+        assert!(reads > 1);
+
+        // Timed out assets are not retried anymore
+        io.set_blocked("slow.test", false);
+        std::thread::sleep(Duration::from_millis(50));
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(io.reads("flaky.test"), reads);
+
+        // A deadline too far to be represented never expires
+        let mut loader = Loader::new();
+        loader.set_asset_timeout(Some(Duration::MAX));
+        loader.enqueue("forever");
+        loader.submit();
+        let path = loader.request_queue.lock().remove(0);
+        let now = Instant::now();
+        loader.work_queue.lock().push(Work {
+            path,
+            handle: HandleUntyped::weak(HandleId::default::<Image>()),
+            attempt: 1,
+            started: now,
+            dependencies: None,
+            retry_time: None,
+        });
+        loader.check_timeouts(now + Duration::from_secs(3600));
+        assert!(!loader.has_failures());
+        assert_eq!(loader.pending_count(), 1);
+    }
+
+    #[test]
+    fn optional() {
+        let mut loader = Loader::new();
+        loader.enqueue("required");
+        loader.enqueue_optional("optional");
+        loader.submit();
+        assert!(!loader.is_optional("required"));
+        assert!(loader.is_optional("optional"));
+
+        let handle = HandleUntyped::weak(HandleId::default::<Image>());
+        loader.finish("required".to_string(), Ok(handle));
+        loader.finish("optional".to_string(), Err(FailureReason::LoadFailed));
+        assert!(loader.has_failures());
+        assert!(loader.is_done());
+        assert!(!loader.is_batch_failed());
+    }
+
+    #[test]
+    fn errors() {
+        let mut loader = Loader::new();
+        assert_eq!(loader.state(), LoaderState::Ready);
+        assert_eq!(loader.try_enqueue("a"), Ok(()));
+        assert_eq!(
+            loader.try_enqueue_optional("a"),
+            Err(LoaderError::DuplicatePath("a".to_string()))
+        );
+        assert!(!loader.is_optional("a"));
+        assert_eq!(loader.try_submit(), Ok(()));
+        assert_eq!(loader.state(), LoaderState::Loading);
+        assert_eq!(loader.try_enqueue("b"), Err(LoaderError::AlreadySubmitted));
+        assert_eq!(loader.try_submit(), Err(LoaderError::AlreadySubmitted));
+
+        let handle = HandleUntyped::weak(HandleId::default::<Image>());
+        loader.finish("a".to_string(), Ok(handle));
+        assert_eq!(loader.state(), LoaderState::Done);
+        assert_eq!(loader.try_enqueue("b"), Err(LoaderError::NotReady));
+        assert_eq!(loader.try_submit(), Err(LoaderError::NotReady));
+
+        loader.reset();
+        assert_eq!(loader.try_enqueue("b"), Ok(()));
+
+        // The infallible variants ignore duplicates instead of panicking
+        loader.enqueue("b");
+        loader.enqueue_weighted("b", 5.0);
+        loader.enqueue_optional("b");
+        loader.enqueue_task("b", async {});
+        assert_eq!(loader.pending_count(), 1);
+        assert!(!loader.is_optional("b"));
+    }
+
+    #[derive(TypeUuid)]
+    #[uuid = "6ea2d6a4-6a2c-4b4b-9d4c-7a3a1c8b1f3e"]
+    struct TestAsset;
+
+    #[derive(TypeUuid)]
+    #[uuid = "0b8f3e2a-1d5b-4a57-8c3e-2f9a6d7e4c10"]
+    struct OtherAsset;
+
+    #[test]
+    fn typed() {
+        let mut app = App::new();
+        app.add_plugin(bevy::core::CorePlugin)
+            .add_plugin(bevy::asset::AssetPlugin)
+            .add_asset::<TestAsset>()
+            .add_asset::<OtherAsset>();
+        let handle_id = HandleId::from("test.asset");
+        app.world
+            .get_resource_mut::<Assets<TestAsset>>()
+            .unwrap()
+            .set_untracked(handle_id, TestAsset);
+        let assets = app.world.get_resource::<Assets<TestAsset>>().unwrap();
+        let other_assets = app.world.get_resource::<Assets<OtherAsset>>().unwrap();
+
+        let mut loader = Loader::new();
+        loader.enqueue("test.asset");
+        loader.submit();
+        assert_eq!(
+            loader.get_typed("test.asset", assets),
+            Err(TakeError::Pending)
+        );
+        loader.finish("test.asset".to_string(), Ok(HandleUntyped::weak(handle_id)));
+
+        assert_eq!(
+            loader.get_typed("test.asset", assets).unwrap().id,
+            handle_id
+        );
+        assert_eq!(
+            loader.take_typed("test.asset", other_assets),
+            Err(TakeError::TypeMismatch(TypeMismatch::of::<OtherAsset>()))
+        );
+        assert!(loader.is_loaded("test.asset"));
+        assert_eq!(
+            loader.take_typed("test.asset", assets).unwrap().id,
+            handle_id
+        );
+        assert!(!loader.is_loaded("test.asset"));
+    }
+
+    #[test]
+    fn tasks() {
+        let mut app = App::new();
+        app.add_plugin(bevy::core::CorePlugin)
+            .add_plugin(bevy::asset::AssetPlugin);
+        let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+        let pool = TaskPool::new();
+
+        let mut loader = Loader::new();
+        loader.enqueue_task("answer", async { 42_u32 });
+        loader.enqueue_io_task("text", async { "config".to_string() });
+        assert_eq!(
+            loader.try_enqueue_task("answer", async {}),
+            Err(LoaderError::DuplicatePath("answer".to_string()))
+        );
+        loader.submit();
+        assert_eq!(loader.total_count(), 2);
+        assert_eq!(loader.pending_count(), 2);
+        assert_eq!(loader.take_task::<u32>("answer"), Err(TakeError::Pending));
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !loader.is_done() {
+            assert!(Instant::now() < deadline);
+            loader.tick(asset_server, &pool, &pool, Instant::now());
+            std::thread::yield_now();
+        }
+        assert_eq!(loader.progress(), 1.0);
+        assert!(loader.is_task_done("answer"));
+        assert_eq!(
+            loader.take_task::<String>("answer"),
+            Err(TakeError::TaskOutputMismatch("alloc::string::String"))
+        );
+        assert_eq!(loader.take_task::<u32>("answer"), Ok(42));
+        assert_eq!(loader.take_task::<u32>("answer"), Err(TakeError::NotFound));
+        assert_eq!(loader.take_task::<String>("text").unwrap(), "config");
+    }
+
+    #[test]
+    fn unsubmitted() {
+        let (mut app, io) = test_app();
+        app.add_plugin(LoaderPlugin);
+        io.add("a.test", "");
+
+        let mut loader = Loader::new();
+        loader.enqueue("a.test");
+        loader.enqueue_task("answer", async { 42_u32 });
+        let entity = app.world.spawn().insert(loader).id();
+
+        // Nothing starts before the batch is submitted
+        for _ in 0..3 {
+            app.update();
+        }
+        let mut loader = app.world.get_mut::<Loader>(entity).unwrap();
+        assert_eq!(loader.state(), LoaderState::Ready);
+        assert!(loader.running_tasks.lock().is_empty());
+        loader.submit();
+        assert_eq!(loader.total_count(), 2);
+        assert_eq!(loader.pending_count(), 2);
+        assert!(!loader.is_done());
+
+        update_until(&mut app, |world| {
+            world.get::<Loader>(entity).unwrap().is_done()
+        });
+        let mut loader = app.world.get_mut::<Loader>(entity).unwrap();
+        assert_eq!(loader.progress(), 1.0);
+        assert!(loader.take("a.test").is_ok());
+        assert_eq!(loader.take_task::<u32>("answer"), Ok(42));
+    }
+
+    #[test]
+    fn pause() {
+        let mut app = App::new();
+        app.add_plugin(bevy::core::CorePlugin)
+            .add_plugin(bevy::asset::AssetPlugin);
+        let asset_server = app.world.get_resource::<AssetServer>().unwrap();
+        let pool = TaskPool::new();
+
+        let mut loader = Loader::new();
+        loader.enqueue("missing.png");
+        loader.enqueue_task("answer", async { 42_u32 });
+        loader.pause();
+        loader.submit();
+        for _ in 0..3 {
+            loader.tick(asset_server, &pool, &pool, Instant::now());
+        }
+        assert!(loader.is_paused());
+        assert_eq!(loader.request_queue.lock().len(), 1);
+        assert_eq!(loader.task_queue.lock().len(), 1);
+        assert_eq!(loader.pending_count(), 2);
+        assert_eq!(loader.progress(), 0.0);
+
+        // Once resumed, requests are issued, and in-flight work completes even if paused again
+        loader.resume();
+        loader.tick(asset_server, &pool, &pool, Instant::now());
+        assert!(loader.request_queue.lock().is_empty());
+        assert!(loader.task_queue.lock().is_empty());
+        loader.pause();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !loader.is_task_done("answer") {
+            assert!(Instant::now() < deadline);
+            loader.tick(asset_server, &pool, &pool, Instant::now());
+            std::thread::yield_now();
+        }
+        assert!(loader.progress() >= 0.5);
+        assert_eq!(loader.take_task::<u32>("answer"), Ok(42));
+    }
+
+    #[test]
+    fn weights() {
+        let mut loader = Loader::new();
+        loader.enqueue_weighted("small", 1.0);
+        loader.enqueue_weighted("large", 3.0);
+        loader.submit();
+        assert_eq!(loader.total_weight(), 4.0);
+        assert_eq!(loader.progress(), 0.0);
+        assert_eq!(loader.min_pending_weight(), 1.0);
+
+        let handle = HandleUntyped::weak(HandleId::default::<Image>());
+        loader.finish("large".to_string(), Ok(handle.clone()));
+        assert_eq!(loader.progress(), 0.75);
+        assert_eq!(loader.pending_weight(), 1.0);
+        loader.finish("small".to_string(), Ok(handle));
+        assert_eq!(loader.progress(), 1.0);
+        assert!(loader.is_done());
+
+        // Automatic weights from file size, with fallback to the average size
+        let folder =
+            std::env::temp_dir().join(format!("bootloader-weights-{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("a.bin"), [0_u8; 100]).unwrap();
+        std::fs::write(folder.join("b.bin"), [0_u8; 300]).unwrap();
+        let mut loader = Loader::new();
+        loader.set_auto_weight(true);
+        loader.enqueue("a.bin");
+        loader.enqueue("b.bin");
+        loader.enqueue("missing.bin");
+        loader.enqueue_weighted("explicit.bin", 50.0);
+        loader.submit();
+        loader.resolve_auto_weights(Some(&folder));
+        std::fs::remove_dir_all(&folder).unwrap();
+        assert_eq!(loader.total_weight(), 100.0 + 300.0 + 200.0 + 50.0);
+    }
+
+    #[test]
+    fn eta() {
+        let mut loader = Loader::new();
+        assert_eq!(loader.estimated_remaining(), None);
+        assert_eq!(loader.elapsed(), Duration::ZERO);
+        loader.enqueue_weighted("small", 1.0);
+        loader.enqueue_weighted("large", 3.0);
+        loader.submit();
+        let submit_time = loader.submit_time.unwrap();
+
+        // No estimate until something completes
+        loader.update_eta(submit_time + Duration::from_secs(1));
+        assert_eq!(loader.estimated_remaining(), None);
+
+        // A quarter of the weight took 2 seconds, so 6 more seconds are needed
+        let handle = HandleUntyped::weak(HandleId::default::<Image>());
+        loader.request_queue.lock().clear();
+        loader.record_finish_time("small", submit_time, submit_time + Duration::from_secs(2));
+        loader.finish("small".to_string(), Ok(handle.clone()));
+        assert_eq!(loader.finish_time("small"), Some(Duration::from_secs(2)));
+        assert_eq!(loader.finish_time("large"), None);
+        loader.update_eta(submit_time + Duration::from_secs(2));
+        assert_eq!(loader.estimated_remaining(), Some(Duration::from_secs(6)));
+
+        // Without progress, the estimate counts down but moves toward the new raw estimate
+        loader.update_eta(submit_time + Duration::from_secs(3));
+        let remaining = loader.estimated_remaining().unwrap().as_secs_f32();
+        assert!(remaining > 5.0 && remaining < 9.0);
+
+        loader.finish("large".to_string(), Ok(handle));
+        loader.update_eta(submit_time + Duration::from_secs(4));
+        assert_eq!(loader.estimated_remaining(), Some(Duration::ZERO));
+        let elapsed = loader.elapsed();
+        assert_eq!(loader.elapsed(), elapsed);
+
+        // A tiny or invalid progress early in a batch doesn't overflow the estimate
+        let mut eta = Eta::default();
+        eta.update(f32::NAN, Duration::from_secs(1));
+        assert_eq!(eta.remaining(), None);
+        eta.update(f32::MIN_POSITIVE, Duration::from_secs(2));
+        assert_eq!(
+            eta.remaining(),
+            Some(Duration::from_secs_f32(MAX_ETA_SECONDS))
+        );
+    }
+
+    #[test]
+    fn timings() {
+        let mut timings = LoadTimings::new();
+        timings.insert("fast", Duration::from_secs(1));
+        timings.insert("slow", Duration::from_secs(3));
+        timings.insert("explicit", Duration::from_secs(4));
+
+        let mut loader = Loader::new();
+        loader.enqueue("fast");
+        loader.enqueue("slow");
+        loader.enqueue("unknown");
+        loader.enqueue_weighted("explicit", 5.0);
+        loader.submit();
+        assert_eq!(loader.expected_duration(), None);
+        loader.apply_timings(&timings, None);
+        assert_eq!(loader.expected_duration(), Some(Duration::from_secs(4)));
+        assert_eq!(loader.total_weight(), 1.0 + 3.0 + 2.0 + 5.0);
+
+        // The time-based progress never falls behind the actual progress
+        let handle = HandleUntyped::weak(HandleId::default::<Image>());
+        loader.request_queue.lock().clear();
+        loader.finish("explicit".to_string(), Ok(handle.clone()));
+        assert!((loader.time_progress() - 5.0 / 11.0).abs() < 1e-6);
+
+        // Timings are recorded once the batch completed, from the start of each asset
+        let start = Instant::now();
+        for (index, path) in ["fast", "slow", "unknown"].into_iter().enumerate() {
+            assert!(!loader.record_timings(&mut timings));
+            let now = start + Duration::from_secs(index as u64 + 1);
+            loader.record_finish_time(path, start, now);
+            loader.finish(path.to_string(), Ok(handle.clone()));
+        }
+        assert_eq!(loader.time_progress(), 1.0);
+        assert!(loader.record_timings(&mut timings));
+        assert!(!loader.record_timings(&mut timings));
+        assert_eq!(timings.len(), 4);
+        assert_eq!(timings.get("unknown"), Some(Duration::from_secs(3)));
+
+        // Unchanged timings don't need to be saved again
+        loader.reset();
+        loader.enqueue("fast");
+        loader.submit();
+        loader.request_queue.lock().clear();
+        loader.record_finish_time("fast", start, start + Duration::from_secs(1));
+        loader.finish("fast".to_string(), Ok(handle.clone()));
+        assert!(!loader.record_timings(&mut timings));
+
+        // Unknown batches keep their weights
+        loader.reset();
+        loader.enqueue("other");
+        loader.submit();
+        loader.apply_timings(&LoadTimings::new(), Some(2.0));
+        assert_eq!(loader.expected_duration(), None);
+        assert_eq!(loader.total_weight(), 1.0);
+    }
+
+    #[test]
+    fn append() {
+        let mut loader = Loader::new();
+        loader.append("level");
+        loader.submit();
+        assert_eq!(loader.total_count(), 1);
+
+        // Complete the first asset, then append more while the batch still loads
+        let handle = HandleUntyped::weak(HandleId::default::<Image>());
+        loader.request_queue.lock().clear();
+        loader.append("terrain");
+        loader.finish("level".to_string(), Ok(handle.clone()));
+        assert_eq!(loader.state(), LoaderState::Loading);
+        assert_eq!(
+            loader.try_append("level"),
+            Err(LoaderError::DuplicatePath("level".to_string()))
+        );
+        // Taking the asset out doesn't allow appending it again
+        assert!(loader.take("level").is_ok());
+        assert_eq!(
+            loader.try_append("level"),
+            Err(LoaderError::DuplicatePath("level".to_string()))
+        );
+        loader.append_weighted("music", 2.0);
+        assert_eq!(loader.total_count(), 3);
+        assert_eq!(loader.pending_count(), 2);
+        assert_eq!(loader.total_weight(), 4.0);
+        assert_eq!(loader.progress(), 0.25);
+
+        loader.request_queue.lock().clear();
+        loader.finish("terrain".to_string(), Ok(handle.clone()));
+        loader.finish("music".to_string(), Ok(handle.clone()));
+        assert!(loader.is_done());
+        assert_eq!(loader.try_append("late"), Err(LoaderError::NotReady));
+
+        // Appended assets are weighted from their timing like enqueued ones
+        let mut timings = LoadTimings::new();
+        timings.insert("level", Duration::from_secs(2));
+        timings.insert("terrain", Duration::from_secs(3));
+        let mut loader = Loader::new();
+        loader.enqueue("level");
+        loader.submit();
+        loader.apply_timings(&timings, None);
+        assert_eq!(loader.total_weight(), 2.0);
+        loader.append("terrain");
+        loader.append("unknown");
+        loader.request_queue.lock().clear();
+        loader.finish("level".to_string(), Ok(handle.clone()));
+        loader.apply_timings(&timings, Some(0.5));
+        assert_eq!(loader.total_weight(), 2.0 + 3.0 + 0.5);
+        assert_eq!(loader.pending_weight(), 3.0 + 0.5);
+
+        // Or from their file size
+        let folder = std::env::temp_dir().join(format!("bootloader-append-{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("a.bin"), [0_u8; 100]).unwrap();
+        std::fs::write(folder.join("b.bin"), [0_u8; 300]).unwrap();
+        let mut loader = Loader::new();
+        loader.set_auto_weight(true);
+        loader.enqueue("a.bin");
+        loader.submit();
+        loader.resolve_auto_weights(Some(&folder));
+        loader.append("b.bin");
+        loader.request_queue.lock().clear();
+        loader.finish("a.bin".to_string(), Ok(handle));
+        loader.resolve_auto_weights(Some(&folder));
+        std::fs::remove_dir_all(&folder).unwrap();
+        assert_eq!(loader.total_weight(), 100.0 + 300.0);
+        assert_eq!(loader.progress(), 0.25);
+    }
+
+    #[test]
+    fn cancel() {
+        let mut loader = Loader::new();
+        assert_eq!(loader.try_cancel(), Err(LoaderError::NotLoading));
+        loader.enqueue("done");
+        loader.enqueue("pending");
+        loader.enqueue("requested");
+        loader.enqueue_task("task", async { 42_u32 });
+        loader.submit();
+
+        // Simulate some assets being processed by the asset server
+        let handle = HandleUntyped::weak(HandleId::default::<Image>());
+        loader
+            .request_queue
+            .lock()
+            .retain(|path| path == "requested");
+        loader.work_queue.lock().push(Work {
+            path: "pending".to_string(),
+            handle: handle.clone(),
+            attempt: 1,
+            started: Instant::now(),
+            dependencies: None,
+            retry_time: None,
+        });
+        loader.finish("done".to_string(), Ok(handle.clone()));
+
+        let progress = loader.progress();
+        assert_eq!(progress, 0.25);
+        loader.cancel();
+        assert!(loader.is_cancelled());
+        assert!(!loader.is_done());
+        // The progress freezes instead of jumping to 100%
+        assert_eq!(loader.progress(), progress);
+        assert_eq!(loader.pending_count(), 0);
+        assert_eq!(loader.estimated_remaining(), None);
+        assert!(loader.work_queue.lock().is_empty());
+        assert!(loader.task_queue.lock().is_empty());
+        assert!(loader.take("done").is_ok());
+        assert_eq!(loader.try_cancel(), Err(LoaderError::NotLoading));
+        assert_eq!(loader.try_enqueue("other"), Err(LoaderError::NotReady));
+
+        // The notification survives a reset
+        loader.reset();
+        assert_eq!(loader.progress(), 1.0);
+        let mut paths = std::mem::take(&mut *loader.cancelled.lock());
+        paths.sort();
+        assert_eq!(paths, ["pending", "requested", "task"]);
+
+        // Same for a weighted batch
+        let mut loader = Loader::new();
+        loader.enqueue_weighted("done", 3.0);
+        loader.enqueue_weighted("pending", 1.0);
+        loader.submit();
+        loader.finish("done".to_string(), Ok(handle));
+        loader.cancel();
+        assert_eq!(loader.progress(), 0.75);
+    }
+
+    #[test]
+    fn bytes() {
+        let folder = std::env::temp_dir().join(format!("bootloader-bytes-{}", std::process::id()));
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("a.bin"), [0_u8; 100]).unwrap();
+        std::fs::write(folder.join("b.bin"), [0_u8; 300]).unwrap();
+        let mut loader = Loader::new();
+        loader.enqueue("a.bin");
+        loader.enqueue("b.bin#first");
+        loader.enqueue("b.bin#second");
+        loader.enqueue("c.bin");
+        loader.submit();
+        assert_eq!(loader.bytes_total(), 0);
+        assert_eq!(loader.progress_bytes(), loader.progress());
+
+        // File sizes are read upfront, except for missing files only known once read
+        let stats = AssetIoStats::default();
+        loader.update_bytes(&stats, Some(&folder));
+        std::fs::remove_dir_all(&folder).unwrap();
+        assert_eq!(loader.bytes_loaded(), 0);
+        assert_eq!(loader.bytes_total(), 100 + 300);
+        assert_eq!(loader.progress_bytes(), loader.progress());
+
+        // Labeled assets share the bytes of their file
+        let asset_io = crate::ProgressAssetIo::new(FileAssetIo::new(&folder));
+        let stats = asset_io.stats();
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("b.bin"), [0_u8; 300]).unwrap();
+        std::fs::write(folder.join("c.bin"), [0_u8; 200]).unwrap();
+        future::block_on(bevy::asset::AssetIo::load_path(
+            &asset_io,
+            Path::new("b.bin"),
+        ))
+        .unwrap();
+        loader.update_bytes(&stats, Some(&folder));
+        assert_eq!(loader.bytes_loaded(), 300);
+        assert_eq!(loader.bytes_total(), 100 + 300);
+        // Falls back to the asset count while the size of "c.bin" is unknown
+        assert_eq!(loader.progress_bytes(), loader.progress());
+
+        future::block_on(bevy::asset::AssetIo::load_path(
+            &asset_io,
+            Path::new("c.bin"),
+        ))
+        .unwrap();
+        std::fs::remove_dir_all(&folder).unwrap();
+        loader.update_bytes(&stats, Some(&folder));
+        assert_eq!(loader.bytes_loaded(), 300 + 200);
+        assert_eq!(loader.bytes_total(), 100 + 300 + 200);
+        assert!((loader.progress_bytes() - 5.0 / 6.0).abs() < 1e-6);
+
+        // The size of appended files is looked up too
+        std::fs::create_dir_all(&folder).unwrap();
+        std::fs::write(folder.join("d.bin"), [0_u8; 400]).unwrap();
+        loader.request_queue.lock().clear();
+        loader.append("d.bin");
+        loader.update_bytes(&stats, Some(&folder));
+        std::fs::remove_dir_all(&folder).unwrap();
+        assert_eq!(loader.bytes_total(), 100 + 300 + 200 + 400);
+        assert_eq!(loader.progress_bytes(), 0.5);
+    }
 }
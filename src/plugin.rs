@@ -1,22 +1,157 @@
-use bevy::prelude::*;
+use bevy::{ecs::schedule::StateData, prelude::*};
 
-use crate::{boot::update_boot, loader::LoaderPlugin};
+use crate::{
+    boot::{update_boot, Boot, BootAssets, BootCancelled, BootCompleted, BootFailed, BootSystem},
+    loader::LoaderPlugin,
+};
 
 /// Plugin to add systems related to [`Boot`] and [`Loader`].
 ///
 /// This plugin is entirely optional. If you want more control, you can instead add manually
 /// the relevant systems or plugins for the component you need:
 ///
-/// - [`Boot`]: add the [`update_boot()`] system.
+/// - [`Boot`]: add the [`update_boot()`] system, insert the [`BootAssets`] resource, and
+///   register the [`BootCompleted`], [`BootFailed`], and [`BootCancelled`] events.
 /// - [`Loader`]: add the [`LoaderPlugin`] plugin.
 ///
+/// To run the boot systems only in a given app state, and automatically transition to another
+/// state once boot completes, use [`BootloaderPlugin::with_states()`] instead.
+///
 /// [`Boot`]: crate::boot::Boot
+/// [`BootAssets`]: crate::boot::BootAssets
+/// [`BootCompleted`]: crate::boot::BootCompleted
+/// [`BootFailed`]: crate::boot::BootFailed
+/// [`BootCancelled`]: crate::boot::BootCancelled
 /// [`Loader`]: crate::loader::Loader
 #[derive(Debug, Clone, Copy)]
 pub struct BootloaderPlugin;
 
+impl BootloaderPlugin {
+    /// Create a plugin running the boot systems only while the app is in the `boot` state, and
+    /// transitioning to the `next` state once the boot sequence completes.
+    ///
+    /// The app state `S` must be registered separately with [`App::add_state()`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use bevy::prelude::*;
+    /// # use bevy_bootloader::*;
+    /// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    /// enum AppState {
+    ///     Boot,
+    ///     MainMenu,
+    ///     Error,
+    /// }
+    ///
+    /// App::default()
+    ///     .add_plugins(DefaultPlugins)
+    ///     .add_state(AppState::Boot)
+    ///     .add_plugin(
+    ///         BootloaderPlugin::with_states(AppState::Boot, AppState::MainMenu)
+    ///             .with_failure_state(AppState::Error),
+    ///     )
+    ///     .run();
+    /// ```
+    pub fn with_states<S: StateData>(boot: S, next: S) -> BootloaderStatePlugin<S> {
+        BootloaderStatePlugin {
+            boot,
+            next,
+            failure: None,
+        }
+    }
+}
+
 impl Plugin for BootloaderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(update_boot).add_plugin(LoaderPlugin);
+        app.init_resource::<BootAssets>()
+            .add_event::<BootCompleted>()
+            .add_event::<BootFailed>()
+            .add_event::<BootCancelled>()
+            .add_system(update_boot.label(BootSystem::UpdateBoot))
+            .add_plugin(LoaderPlugin);
+    }
+}
+
+/// Plugin to add systems related to [`Boot`] and [`Loader`], running only in a given app state.
+///
+/// This is a variant of [`BootloaderPlugin`] where the [`update_boot()`] system only runs while
+/// the app is in the boot state. Once all boot sequences complete, the plugin transitions the app
+/// to the next state. If any boot sequence fails because some required asset failed to load, the
+/// plugin transitions the app to the failure state if any, or otherwise stays in the boot state.
+/// A cancelled boot sequence doesn't transition the app state by itself.
+///
+/// Create this plugin with [`BootloaderPlugin::with_states()`].
+///
+/// [`Loader`]: crate::loader::Loader
+#[derive(Debug, Clone)]
+pub struct BootloaderStatePlugin<S: StateData> {
+    /// State during which the boot systems run.
+    boot: S,
+    /// State to transition to once boot completes.
+    next: S,
+    /// Optional state to transition to if boot fails.
+    failure: Option<S>,
+}
+
+impl<S: StateData> BootloaderStatePlugin<S> {
+    /// Set the state to transition to if the boot sequence fails.
+    pub fn with_failure_state(mut self, failure: S) -> Self {
+        self.failure = Some(failure);
+        self
+    }
+}
+
+impl<S: StateData> Plugin for BootloaderStatePlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BootAssets>()
+            .add_event::<BootCompleted>()
+            .add_event::<BootFailed>()
+            .add_event::<BootCancelled>()
+            .insert_resource(BootStates {
+                next: self.next.clone(),
+                failure: self.failure.clone(),
+            })
+            .add_system_set(
+                SystemSet::on_update(self.boot.clone())
+                    .with_system(update_boot.label(BootSystem::UpdateBoot))
+                    .with_system(transition_boot_state::<S>.after(BootSystem::UpdateBoot)),
+            )
+            .add_plugin(LoaderPlugin);
+    }
+}
+
+/// States to transition to at the end of the boot sequence.
+struct BootStates<S: StateData> {
+    /// State to transition to once boot completes.
+    next: S,
+    /// Optional state to transition to if boot fails.
+    failure: Option<S>,
+}
+
+/// Transition the app state once all boot sequences complete, or once any of them fails.
+fn transition_boot_state<S: StateData>(
+    boot_states: Res<BootStates<S>>,
+    mut state: ResMut<State<S>>,
+    mut completed_events: EventReader<BootCompleted>,
+    mut failed_events: EventReader<BootFailed>,
+    query: Query<&Boot>,
+) {
+    // Completed and cancelled boot entities are only despawned at the end of the stage, so are
+    // still visible here; they're ignored since they're marked as such.
+    let all_done = query
+        .iter()
+        .all(|boot| boot.is_completed() || boot.is_cancelled());
+    let next = if failed_events.iter().last().is_some() {
+        boot_states.failure.as_ref()
+    } else if completed_events.iter().last().is_some() && all_done {
+        Some(&boot_states.next)
+    } else {
+        None
+    };
+    if let Some(next) = next {
+        if let Err(err) = state.set(next.clone()) {
+            debug!("Failed to transition to state {:?}: {}", next, err);
+        }
     }
 }
@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+
+use crate::boot::{Boot, BootScreen, BootSystem};
+
+/// Style of the default boot screen spawned by the [`DefaultBootScreenPlugin`].
+///
+/// Insert this resource before adding the plugin to customize the boot screen. Changes made
+/// while the boot screen is visible are applied on the next frame, except for the clear color
+/// which is only applied when the boot screen is spawned.
+#[derive(Debug, Clone, Copy)]
+pub struct BootScreenStyle {
+    /// Length of the progress bar, in pixels.
+    pub size: f32,
+    /// Thickness of the progress bar, in pixels.
+    pub thickness: f32,
+    /// Color of the foreground part of the progress bar, showing the progress.
+    pub color: Color,
+    /// Color of the background part of the progress bar.
+    pub background_color: Color,
+    /// Position of the center of the progress bar, in pixels, relative to the center of the
+    /// screen.
+    pub position: Vec2,
+    /// Clear color of the screen while the boot screen is visible. The previous clear color is
+    /// restored once the boot sequence completes.
+    pub clear_color: Color,
+}
+
+impl Default for BootScreenStyle {
+    fn default() -> Self {
+        BootScreenStyle {
+            size: 200.0,
+            thickness: 3.0,
+            color: Color::rgba(0.3, 0.4, 0.3, 1.0),
+            background_color: Color::rgba(0.2, 0.3, 0.2, 1.0),
+            position: Vec2::ZERO,
+            clear_color: Color::rgba(0.1, 0.1, 0.1, 1.0),
+        }
+    }
+}
+
+/// Plugin displaying a default boot screen made of a simple progress bar.
+///
+/// When a [`Boot`] entity is spawned, the plugin spawns a 2D camera and a progress bar formed of
+/// a background and a foreground sprites, whose style is controlled by the [`BootScreenStyle`]
+/// resource. The progress bar is driven by [`Boot::smoothed_progress()`]. All those entities are
+/// marked with the [`BootScreen`] component, and are despawned once the boot sequence completes.
+///
+/// If several boot sequences run concurrently, a single boot screen is spawned, showing the
+/// average progress of all of them.
+///
+/// This plugin relies on the [`BootloaderPlugin`] to update the [`Boot`], which must be added
+/// separately.
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_bootloader::*;
+/// App::default()
+///     .add_plugins(DefaultPlugins)
+///     .insert_resource(BootScreenStyle {
+///         color: Color::ORANGE,
+///         ..Default::default()
+///     })
+///     .add_plugin(BootloaderPlugin)
+///     .add_plugin(DefaultBootScreenPlugin)
+///     .run();
+/// ```
+///
+/// [`BootloaderPlugin`]: crate::BootloaderPlugin
+#[derive(Debug, Clone, Copy)]
+pub struct DefaultBootScreenPlugin;
+
+impl Plugin for DefaultBootScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BootScreenStyle>()
+            .add_system(spawn_boot_screen.before(BootSystem::UpdateBoot))
+            .add_system(update_boot_screen.after(BootSystem::UpdateBoot));
+    }
+}
+
+/// Marker component for the entities of the default boot screen.
+#[derive(Component)]
+struct DefaultBootScreen;
+
+/// Marker component for the foreground sprite of the default progress bar.
+#[derive(Component)]
+struct ProgressBar;
+
+/// Clear color in use before the default boot screen was spawned, to restore once it's gone.
+struct PreviousClearColor(Color);
+
+fn spawn_boot_screen(
+    mut commands: Commands,
+    style: Res<BootScreenStyle>,
+    clear_color: Option<ResMut<ClearColor>>,
+    added_query: Query<(), Added<Boot>>,
+    screen_query: Query<(), With<DefaultBootScreen>>,
+) {
+    if added_query.is_empty() || !screen_query.is_empty() {
+        return;
+    }
+
+    if let Some(mut clear_color) = clear_color {
+        commands.insert_resource(PreviousClearColor(clear_color.0));
+        clear_color.0 = style.clear_color;
+    }
+
+    commands
+        .spawn_bundle(OrthographicCameraBundle::new_2d())
+        .insert(DefaultBootScreen)
+        .insert(BootScreen);
+
+    // Spawn a progress bar formed of a background fixed sprite and a foreground sprite
+    // whose size will be animated to form the progress bar.
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: style.background_color,
+                custom_size: Some(Vec2::new(style.size, style.thickness)),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(style.position.extend(0.0)),
+            ..Default::default()
+        })
+        .insert(DefaultBootScreen)
+        .insert(BootScreen);
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: style.color,
+                custom_size: Some(Vec2::ZERO), // invisible initially
+                ..Default::default()
+            },
+            // Slightly in front of the background sprite
+            transform: Transform::from_translation(style.position.extend(0.1)),
+            ..Default::default()
+        })
+        .insert(DefaultBootScreen)
+        .insert(ProgressBar)
+        .insert(BootScreen);
+}
+
+fn update_boot_screen(
+    mut commands: Commands,
+    style: Res<BootScreenStyle>,
+    previous_clear_color: Option<Res<PreviousClearColor>>,
+    clear_color: Option<ResMut<ClearColor>>,
+    boot_query: Query<&Boot>,
+    mut sprite_query: Query<
+        (&mut Transform, &mut Sprite, Option<&ProgressBar>),
+        With<DefaultBootScreen>,
+    >,
+) {
+    // Once all boot sequences completed and their entities got despawned, restore the clear color
+    if boot_query.is_empty() {
+        if let Some(previous_clear_color) = previous_clear_color {
+            if let Some(mut clear_color) = clear_color {
+                clear_color.0 = previous_clear_color.0;
+            }
+            commands.remove_resource::<PreviousClearColor>();
+        }
+        return;
+    }
+
+    // Show the average progress of all boot sequences
+    let count = boot_query.iter().count();
+    let smoothed_progress =
+        boot_query.iter().map(Boot::smoothed_progress).sum::<f32>() / count as f32;
+
+    for (mut transform, mut sprite, progress_bar) in sprite_query.iter_mut() {
+        if progress_bar.is_some() {
+            let size = style.size * smoothed_progress;
+            // The sprite is a rect centered at the transform position, so move by half size to
+            // keep aligned to the left while width grows.
+            transform.translation.x = style.position.x + (size - style.size) / 2.;
+            transform.translation.y = style.position.y;
+            sprite.custom_size = Some(Vec2::new(size, style.thickness));
+            sprite.color = style.color;
+        } else {
+            transform.translation.x = style.position.x;
+            transform.translation.y = style.position.y;
+            sprite.custom_size = Some(Vec2::new(style.size, style.thickness));
+            sprite.color = style.background_color;
+        }
+    }
+}
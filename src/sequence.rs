@@ -0,0 +1,336 @@
+use bevy::prelude::*;
+use std::{collections::HashMap, fmt};
+
+use crate::{boot::target_progress, loader::Loader};
+
+/// Status of a custom [`BootPhase`] task, returned by the task each time it's polled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PhaseStatus {
+    /// The task is still running, with the given progress in \[0:1\].
+    InProgress(f32),
+    /// The task completed successfully.
+    Done,
+    /// The task failed, which fails the entire boot sequence.
+    Failed,
+}
+
+/// Closure enqueuing the assets of a loading [`BootPhase`].
+type PhaseSetup = Box<dyn FnOnce(&mut Loader) + Send + Sync>;
+
+/// Work done by a [`BootPhase`].
+enum PhaseWork {
+    /// Load a batch of assets with the [`Loader`] of the boot entity. The setup closure is
+    /// consumed when the phase starts.
+    Load(Option<PhaseSetup>),
+    /// Poll a custom task each frame until it completes.
+    Task(Box<dyn FnMut() -> PhaseStatus + Send + Sync>),
+}
+
+/// A single phase of a [`BootSequence`].
+///
+/// A phase either loads a batch of assets with the [`Loader`] of the boot entity, or runs a
+/// custom task polled each frame. Each phase has a weight, which determines how much of the
+/// overall boot progress it accounts for.
+pub struct BootPhase {
+    /// Name of the phase, for reporting.
+    name: String,
+    /// Weight of the phase in the overall progress.
+    weight: f32,
+    /// Work done by the phase.
+    work: PhaseWork,
+}
+
+impl BootPhase {
+    /// Create a phase loading a batch of assets.
+    ///
+    /// When the phase starts, the [`Loader`] of the boot entity is reset, then passed to the
+    /// `setup` closure to enqueue the assets of the batch, and finally submitted. The phase
+    /// completes once the loader is done, and fails the boot sequence if the loader batch fails.
+    pub fn load(
+        name: impl Into<String>,
+        setup: impl FnOnce(&mut Loader) + Send + Sync + 'static,
+    ) -> Self {
+        BootPhase {
+            name: name.into(),
+            weight: 1.0,
+            work: PhaseWork::Load(Some(Box::new(setup))),
+        }
+    }
+
+    /// Create a phase running a custom task.
+    ///
+    /// The `task` closure is polled once per frame while the phase is active, until it returns
+    /// either [`PhaseStatus::Done`] or [`PhaseStatus::Failed`].
+    pub fn task(
+        name: impl Into<String>,
+        task: impl FnMut() -> PhaseStatus + Send + Sync + 'static,
+    ) -> Self {
+        BootPhase {
+            name: name.into(),
+            weight: 1.0,
+            work: PhaseWork::Task(Box::new(task)),
+        }
+    }
+
+    /// Set the weight of the phase in the overall progress. The default weight is `1.0`.
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.max(0.0);
+        self
+    }
+
+    /// Get the name of the phase.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the weight of the phase in the overall progress.
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+}
+
+impl fmt::Debug for BootPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let work = match &self.work {
+            PhaseWork::Load(_) => "load",
+            PhaseWork::Task(_) => "task",
+        };
+        f.debug_struct("BootPhase")
+            .field("name", &self.name)
+            .field("weight", &self.weight)
+            .field("work", &work)
+            .finish()
+    }
+}
+
+/// Component describing a boot sequence made of several ordered phases.
+///
+/// Insert this component on a boot entity, alongside the [`Boot`] and [`Loader`] components, to
+/// run several phases one after the other. Each phase starts only once the previous one
+/// completed. With a boot sequence, the [`Loader`] of the boot entity is managed by the sequence,
+/// and should not be submitted manually.
+///
+/// The [`Boot`] reports the overall progress of the sequence, where each phase accounts for a
+/// fraction of the progress proportional to its weight, as well as the progress of the current
+/// phase (see [`Boot::phase_progress()`]). The boot sequence completes once all phases completed,
+/// and fails as soon as any phase fails.
+///
+/// # Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_bootloader::*;
+/// fn setup_boot(mut commands: Commands) {
+///     let sequence = BootSequence::new()
+///         .with_phase(BootPhase::load("splash", |loader| loader.enqueue("logo.png")))
+///         .with_phase(
+///             BootPhase::load("ui", |loader| {
+///                 loader.enqueue("ui/atlas.png");
+///                 loader.enqueue("ui/font.ttf");
+///             })
+///             .with_weight(3.0),
+///         )
+///         .with_phase(BootPhase::task("saves", || {
+///             // ...scan save files...
+///             PhaseStatus::Done
+///         }));
+///     commands
+///         .spawn_bundle(BootBundle::default())
+///         .insert(sequence);
+/// }
+/// ```
+///
+/// [`Boot`]: crate::Boot
+/// [`Boot::phase_progress()`]: crate::Boot::phase_progress
+#[derive(Debug, Default, Component)]
+pub struct BootSequence {
+    /// Ordered list of phases.
+    phases: Vec<BootPhase>,
+    /// Index of the current phase.
+    current: usize,
+    /// Has the current phase started?
+    started: bool,
+    /// Handles of the assets loaded by all completed phases, keyed by asset path.
+    handles: HashMap<String, HandleUntyped>,
+    /// Number of assets loaded by all completed phases.
+    asset_count: usize,
+}
+
+/// Status of a [`BootSequence`] after an update.
+pub(crate) enum SequenceStatus {
+    /// The sequence is running, with the given overall progress and current phase progress.
+    InProgress {
+        /// Overall progress of the sequence, in \[0:1\].
+        progress: f32,
+        /// Progress of the current phase, in \[0:1\].
+        phase_progress: f32,
+    },
+    /// All the phases completed.
+    Done,
+    /// The current phase failed.
+    Failed,
+}
+
+impl BootSequence {
+    /// Create a new empty boot sequence.
+    pub fn new() -> Self {
+        BootSequence::default()
+    }
+
+    /// Append a phase to the sequence.
+    pub fn with_phase(mut self, phase: BootPhase) -> Self {
+        self.add_phase(phase);
+        self
+    }
+
+    /// Append a phase to the sequence.
+    pub fn add_phase(&mut self, phase: BootPhase) {
+        self.phases.push(phase);
+    }
+
+    /// Get all the phases of the sequence, in order.
+    pub fn phases(&self) -> &[BootPhase] {
+        &self.phases
+    }
+
+    /// Get the index of the current phase, or `None` if all phases completed.
+    pub fn current_index(&self) -> Option<usize> {
+        if self.current < self.phases.len() {
+            Some(self.current)
+        } else {
+            None
+        }
+    }
+
+    /// Get the current phase, or `None` if all phases completed.
+    pub fn current_phase(&self) -> Option<&BootPhase> {
+        self.phases.get(self.current)
+    }
+
+    /// Is the sequence done, that is did all its phases complete?
+    pub fn is_done(&self) -> bool {
+        self.current >= self.phases.len()
+    }
+
+    /// Number of assets loaded by all completed phases.
+    pub(crate) fn asset_count(&self) -> usize {
+        self.asset_count
+    }
+
+    /// Take the handles of all the assets loaded by the completed phases.
+    pub(crate) fn take_handles(&mut self) -> HashMap<String, HandleUntyped> {
+        std::mem::take(&mut self.handles)
+    }
+
+    /// Update the sequence, starting phases as needed and polling the current one.
+    pub(crate) fn update(&mut self, loader: &mut Loader) -> SequenceStatus {
+        while let Some(phase) = self.phases.get_mut(self.current) {
+            let phase_progress = match &mut phase.work {
+                PhaseWork::Load(setup) => {
+                    if !self.started {
+                        loader.reset();
+                        if let Some(setup) = setup.take() {
+                            setup(loader);
+                        }
+                        loader.submit();
+                        self.started = true;
+                    }
+                    if loader.is_done() {
+                        self.asset_count += loader.loaded_count();
+                        self.handles.extend(loader.take_all());
+                        None
+                    } else if loader.is_batch_failed() {
+                        return SequenceStatus::Failed;
+                    } else {
+                        Some(target_progress(loader))
+                    }
+                }
+                PhaseWork::Task(task) => {
+                    self.started = true;
+                    match task() {
+                        PhaseStatus::InProgress(progress) => Some(progress.clamp(0.0, 1.0)),
+                        PhaseStatus::Done => None,
+                        PhaseStatus::Failed => return SequenceStatus::Failed,
+                    }
+                }
+            };
+
+            match phase_progress {
+                Some(phase_progress) => {
+                    let total_weight: f32 = self.phases.iter().map(BootPhase::weight).sum();
+                    let progress = if total_weight > 0.0 {
+                        let done_weight: f32 = self.phases[..self.current]
+                            .iter()
+                            .map(BootPhase::weight)
+                            .sum();
+                        let weight = self.phases[self.current].weight;
+                        (done_weight + weight * phase_progress) / total_weight
+                    } else {
+                        self.current as f32 / self.phases.len() as f32
+                    };
+                    return SequenceStatus::InProgress {
+                        progress,
+                        phase_progress,
+                    };
+                }
+                None => {
+                    // Phase completed; start the next one right away
+                    trace!("Boot phase '{}' completed", self.phases[self.current].name);
+                    self.current += 1;
+                    self.started = false;
+                }
+            }
+        }
+        SequenceStatus::Done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    #[test]
+    fn phases() {
+        let polls = Arc::new(AtomicU32::new(0));
+        let task_polls = Arc::clone(&polls);
+        let mut sequence = BootSequence::new()
+            .with_phase(BootPhase::load("empty", |_| {}))
+            .with_phase(
+                BootPhase::task("task", move || {
+                    match task_polls.fetch_add(1, Ordering::Relaxed) {
+                        0 => PhaseStatus::InProgress(0.5),
+                        _ => PhaseStatus::Done,
+                    }
+                })
+                .with_weight(2.0),
+            )
+            .with_phase(BootPhase::task("fail", || PhaseStatus::Failed));
+        let mut loader = Loader::new();
+
+        // The empty batch completes immediately, and the task starts right away
+        match sequence.update(&mut loader) {
+            SequenceStatus::InProgress {
+                progress,
+                phase_progress,
+            } => {
+                assert_eq!(phase_progress, 0.5);
+                assert_eq!(progress, (1.0 + 2.0 * 0.5) / 4.0);
+            }
+            _ => panic!("sequence should be in progress"),
+        }
+        assert_eq!(sequence.current_index(), Some(1));
+
+        // The task completes, and the next phase fails
+        assert!(matches!(
+            sequence.update(&mut loader),
+            SequenceStatus::Failed
+        ));
+        assert_eq!(sequence.current_phase().unwrap().name(), "fail");
+        assert_eq!(polls.load(Ordering::Relaxed), 2);
+        assert!(!sequence.is_done());
+    }
+}
@@ -0,0 +1,172 @@
+//! Helpers to run tests against a real [`AssetServer`] reading from memory.
+
+use bevy::{
+    asset::{AssetIo, AssetIoError, AssetLoader, AssetPath, BoxedFuture, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    tasks::IoTaskPool,
+    utils::{Duration, Instant},
+};
+use futures_lite::future;
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Test asset, whose content lists the paths of its dependencies, one per line.
+#[derive(Debug, TypeUuid)]
+#[uuid = "3c3a1e0a-6d1e-4f6b-9b0e-6f8e5d7c2a10"]
+pub(crate) struct TestAsset {
+    /// Handles to the dependencies of the asset.
+    pub dependencies: Vec<Handle<TestAsset>>,
+}
+
+/// Loader for the `.test` files of [`TestAsset`].
+struct TestAssetLoader;
+
+impl AssetLoader for TestAssetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let content = std::str::from_utf8(bytes)?;
+            let paths: Vec<&str> = content.lines().filter(|line| !line.is_empty()).collect();
+            let dependencies = paths
+                .iter()
+                .map(|path| load_context.get_handle(AssetPath::from(*path)))
+                .collect();
+            let mut asset = LoadedAsset::new(TestAsset { dependencies });
+            for path in paths {
+                asset = asset.with_dependency(AssetPath::from(path).to_owned());
+            }
+            load_context.set_default_asset(asset);
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["test"]
+    }
+}
+
+/// State shared between a [`TestAssetIo`] and the test controlling it.
+#[derive(Default)]
+pub(crate) struct TestIoState {
+    /// Content of the files, keyed by path.
+    files: Mutex<HashMap<PathBuf, Vec<u8>>>,
+    /// Number of upcoming reads to fail, keyed by path.
+    failures: Mutex<HashMap<PathBuf, u32>>,
+    /// Paths whose reads are blocked until unblocked.
+    blocked: Mutex<HashSet<PathBuf>>,
+    /// Number of reads made, keyed by path.
+    reads: Mutex<HashMap<PathBuf, u32>>,
+}
+
+impl TestIoState {
+    /// Add a file with the given content.
+    pub fn add(&self, path: &str, content: &str) {
+        self.files
+            .lock()
+            .insert(PathBuf::from(path), content.as_bytes().to_vec());
+    }
+
+    /// Make the next `count` reads of the given path fail.
+    pub fn fail(&self, path: &str, count: u32) {
+        self.failures.lock().insert(PathBuf::from(path), count);
+    }
+
+    /// Block or unblock the reads of the given path.
+    pub fn set_blocked(&self, path: &str, blocked: bool) {
+        let mut blocked_paths = self.blocked.lock();
+        if blocked {
+            blocked_paths.insert(PathBuf::from(path));
+        } else {
+            blocked_paths.remove(Path::new(path));
+        }
+    }
+
+    /// Number of reads of the given path made so far.
+    pub fn reads(&self, path: &str) -> u32 {
+        self.reads.lock().get(Path::new(path)).copied().unwrap_or(0)
+    }
+}
+
+/// Asset I/O reading files from memory, with controllable failures and delays.
+struct TestAssetIo(Arc<TestIoState>);
+
+impl AssetIo for TestAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            while self.0.blocked.lock().contains(path) {
+                future::yield_now().await;
+            }
+            *self.0.reads.lock().entry(path.to_path_buf()).or_default() += 1;
+            if let Some(count) = self.0.failures.lock().get_mut(path) {
+                if *count > 0 {
+                    *count -= 1;
+                    return Err(AssetIoError::NotFound(path.to_path_buf()));
+                }
+            }
+            self.0
+                .files
+                .lock()
+                .get(path)
+                .cloned()
+                .ok_or_else(|| AssetIoError::NotFound(path.to_path_buf()))
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        Err(AssetIoError::NotFound(path.to_path_buf()))
+    }
+
+    fn is_directory(&self, _path: &Path) -> bool {
+        false
+    }
+
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
+
+/// Create an app with an asset server reading [`TestAsset`] files from memory, returning the
+/// state controlling those files.
+///
+/// The app has no loader nor boot plugin; the test adds the ones it needs.
+pub(crate) fn test_app() -> (App, Arc<TestIoState>) {
+    let state = Arc::new(TestIoState::default());
+    let mut app = App::new();
+    app.add_plugin(bevy::core::CorePlugin);
+    let task_pool = app.world.get_resource::<IoTaskPool>().unwrap().0.clone();
+    let asset_server =
+        AssetServer::with_boxed_io(Box::new(TestAssetIo(Arc::clone(&state))), task_pool);
+    app.insert_resource(asset_server)
+        .add_plugin(bevy::asset::AssetPlugin)
+        .add_asset::<TestAsset>()
+        .add_asset_loader(TestAssetLoader);
+    (app, state)
+}
+
+/// Update the app until the condition is true, panicking after a few seconds.
+pub(crate) fn update_until(app: &mut App, mut condition: impl FnMut(&mut World) -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        app.update();
+        if condition(&mut app.world) {
+            return;
+        }
+        assert!(Instant::now() < deadline, "timed out waiting for condition");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
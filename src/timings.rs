@@ -0,0 +1,347 @@
+use bevy::{prelude::*, utils::Duration};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use crate::loader::{Loader, LoaderStage, LoaderState, LoaderSystem};
+
+/// Error returned when reading or writing a [`LoadTimings`] file.
+#[derive(Debug)]
+pub enum LoadTimingsError {
+    /// The file could not be read or written.
+    Io(std::io::Error),
+    /// The file content is not a valid RON serialization of the timings.
+    Ron(ron::Error),
+}
+
+impl std::fmt::Display for LoadTimingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadTimingsError::Io(err) => write!(f, "load timings I/O error: {}", err),
+            LoadTimingsError::Ron(err) => write!(f, "invalid load timings: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for LoadTimingsError {}
+
+impl From<std::io::Error> for LoadTimingsError {
+    fn from(err: std::io::Error) -> Self {
+        LoadTimingsError::Io(err)
+    }
+}
+
+impl From<ron::Error> for LoadTimingsError {
+    fn from(err: ron::Error) -> Self {
+        LoadTimingsError::Ron(err)
+    }
+}
+
+/// Time each asset or task took to load during a previous run, keyed by asset path or task name.
+///
+/// The timings are recorded from the [`Loader`] instances once their batch completes, and
+/// persisted to a RON file to be reused on the next run. A [`Loader`] with known timings uses
+/// them as the weights of its assets and tasks, and to report a progress advancing linearly
+/// with time with [`Loader::time_progress()`]. The [`LoadTimingsPlugin`] takes care of all of
+/// that automatically.
+///
+/// Each timing is the time it took the asset or task to complete from the moment it started, as
+/// reported by [`Loader::finish_time()`], and only the latest timing is kept.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadTimings {
+    /// Timings in seconds, keyed by asset path or task name.
+    timings: BTreeMap<String, f32>,
+}
+
+impl LoadTimings {
+    /// Create an empty collection of timings.
+    pub fn new() -> Self {
+        LoadTimings::default()
+    }
+
+    /// Read the timings from a RON file.
+    ///
+    /// Invalid timings, which are negative, infinite, NaN, or too large to be a duration, are
+    /// discarded with a warning; the file can be edited by the user.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadTimingsError> {
+        let content = std::fs::read_to_string(path)?;
+        let mut timings: LoadTimings = ron::from_str(&content)?;
+        timings.timings.retain(|path, &mut seconds| {
+            let valid = Duration::try_from_secs_f32(seconds).is_ok();
+            if !valid {
+                warn!("Discarding invalid load timing of {}: {}", path, seconds);
+            }
+            valid
+        });
+        Ok(timings)
+    }
+
+    /// Write the timings to a RON file, replacing it if it already exists.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), LoadTimingsError> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Get the timing of the asset or task with the given path or name, if known and valid.
+    pub fn get(&self, path: &str) -> Option<Duration> {
+        self.timings
+            .get(path)
+            .and_then(|&seconds| Duration::try_from_secs_f32(seconds).ok())
+    }
+
+    /// Record the timing of the asset or task with the given path or name, replacing any
+    /// previous timing.
+    ///
+    /// Returns `true` if the timing changed.
+    pub fn insert(&mut self, path: impl Into<String>, duration: Duration) -> bool {
+        let seconds = duration.as_secs_f32();
+        self.timings.insert(path.into(), seconds) != Some(seconds)
+    }
+
+    /// Number of assets and tasks with a known timing.
+    pub fn len(&self) -> usize {
+        self.timings.len()
+    }
+
+    /// Is the collection empty?
+    pub fn is_empty(&self) -> bool {
+        self.timings.is_empty()
+    }
+}
+
+/// Plugin to record the load timings of all [`Loader`] instances, persist them across runs, and
+/// use them to weight the assets and report a time-based progress.
+///
+/// On startup, the plugin reads the [`LoadTimings`] from a RON file, `boot_timings.ron` by
+/// default, and inserts them as a resource; a missing file is not an error, and simply means no
+/// timing is known yet. Each [`Loader`] starting to load a batch then uses the known timings:
+///
+/// - Assets and tasks enqueued without an explicit weight are weighted by their timing in
+///   seconds. Those without timing fall back to a default weight, by default the average timing
+///   of the other assets of the batch. This takes precedence over [`Loader::set_auto_weight()`].
+/// - The expected duration of the batch is the largest timing of its assets and tasks. This
+///   allows [`Loader::time_progress()`], and the progress of the [`Boot`], to advance linearly
+///   with time instead of jumping each time an asset completes.
+///
+/// Once a batch completes, the timings of its assets and tasks are recorded. The file is written
+/// back once no [`Loader`] is loading anymore, typically when booting finishes, and only if any
+/// timing changed.
+///
+/// This plugin must be added after the [`LoaderPlugin`], generally added via the
+/// [`BootloaderPlugin`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_bootloader::*;
+/// App::default()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugin(BootloaderPlugin)
+///     .add_plugin(LoadTimingsPlugin::new("cache/boot_timings.ron").with_default_weight(0.5))
+///     .run();
+/// ```
+///
+/// [`Boot`]: crate::Boot
+/// [`LoaderPlugin`]: crate::LoaderPlugin
+/// [`BootloaderPlugin`]: crate::BootloaderPlugin
+#[derive(Debug, Clone)]
+pub struct LoadTimingsPlugin {
+    /// Path of the RON file storing the timings.
+    path: PathBuf,
+    /// Weight of the assets and tasks without known timing, if not the average timing.
+    default_weight: Option<f32>,
+}
+
+impl Default for LoadTimingsPlugin {
+    fn default() -> Self {
+        LoadTimingsPlugin::new("boot_timings.ron")
+    }
+}
+
+impl LoadTimingsPlugin {
+    /// Create a plugin persisting the timings to the RON file at the given path.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        LoadTimingsPlugin {
+            path: path.into(),
+            default_weight: None,
+        }
+    }
+
+    /// Set the weight of the assets and tasks without known timing.
+    ///
+    /// By default, those use the average timing of the other assets and tasks of their batch.
+    pub fn with_default_weight(mut self, default_weight: f32) -> Self {
+        self.default_weight = Some(default_weight.max(0.0));
+        self
+    }
+}
+
+impl Plugin for LoadTimingsPlugin {
+    fn build(&self, app: &mut App) {
+        let timings = match LoadTimings::load(&self.path) {
+            Ok(timings) => timings,
+            Err(LoadTimingsError::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+                LoadTimings::default()
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to read load timings from {}: {}",
+                    self.path.display(),
+                    err
+                );
+                LoadTimings::default()
+            }
+        };
+        app.insert_resource(timings)
+            .insert_resource(LoadTimingsSettings {
+                path: self.path.clone(),
+                default_weight: self.default_weight,
+                unsaved: false,
+            })
+            .add_system_to_stage(
+                LoaderStage::UpdateLoaders,
+                apply_load_timings.before(LoaderSystem::TickLoaders),
+            )
+            .add_system_to_stage(
+                LoaderStage::UpdateLoaders,
+                record_load_timings.after(LoaderSystem::TickLoaders),
+            );
+    }
+}
+
+/// Settings of the [`LoadTimingsPlugin`].
+struct LoadTimingsSettings {
+    /// Path of the RON file storing the timings.
+    path: PathBuf,
+    /// Weight of the assets and tasks without known timing, if not the average timing.
+    default_weight: Option<f32>,
+    /// Did any timing change since the file was last written?
+    unsaved: bool,
+}
+
+fn apply_load_timings(
+    timings: Res<LoadTimings>,
+    settings: Res<LoadTimingsSettings>,
+    mut query: Query<&mut Loader>,
+) {
+    for mut loader in query.iter_mut() {
+        loader.apply_timings(&timings, settings.default_weight);
+    }
+}
+
+fn record_load_timings(
+    mut timings: ResMut<LoadTimings>,
+    mut settings: ResMut<LoadTimingsSettings>,
+    mut query: Query<&mut Loader>,
+) {
+    let mut loading = false;
+    for mut loader in query.iter_mut() {
+        settings.unsaved |= loader.record_timings(&mut timings);
+        loading |= loader.state() == LoaderState::Loading;
+    }
+    // Wait for all batches to complete, to write the file only once
+    if settings.unsaved && !loading {
+        settings.unsaved = false;
+        if let Err(err) = timings.save(&settings.path) {
+            warn!(
+                "Failed to write load timings to {}: {}",
+                settings.path.display(),
+                err
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{test_app, update_until},
+        LoaderPlugin,
+    };
+
+    #[test]
+    fn save_load() {
+        let mut timings = LoadTimings::new();
+        assert!(timings.is_empty());
+        assert!(timings.insert("texture.png", Duration::from_millis(250)));
+        assert!(timings.insert("level.gltf", Duration::from_secs(2)));
+        assert!(timings.insert("texture.png", Duration::from_millis(500)));
+        assert!(!timings.insert("texture.png", Duration::from_millis(500)));
+        assert_eq!(timings.len(), 2);
+        assert_eq!(timings.get("texture.png"), Some(Duration::from_millis(500)));
+        assert_eq!(timings.get("unknown.png"), None);
+
+        let path =
+            std::env::temp_dir().join(format!("bootloader-timings-{}.ron", std::process::id()));
+        timings.save(&path).unwrap();
+        let loaded = LoadTimings::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, timings);
+        assert!(matches!(
+            LoadTimings::load(&path),
+            Err(LoadTimingsError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn invalid() {
+        let path =
+            std::env::temp_dir().join(format!("bootloader-invalid-{}.ron", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"(timings: {"a.png": -1.0, "b.png": inf, "c.png": NaN, "d.png": 1e30, "e.png": 0.5})"#,
+        )
+        .unwrap();
+        let timings = LoadTimings::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings.get("e.png"), Some(Duration::from_millis(500)));
+
+        // Invalid values inserted by other means are ignored too
+        let timings = LoadTimings {
+            timings: [("a.png".to_string(), -1.0)].into_iter().collect(),
+        };
+        assert_eq!(timings.get("a.png"), None);
+    }
+
+    #[test]
+    fn plugin() {
+        let path =
+            std::env::temp_dir().join(format!("bootloader-plugin-{}.ron", std::process::id()));
+        let (mut app, io) = test_app();
+        app.add_plugin(LoaderPlugin)
+            .add_plugin(LoadTimingsPlugin::new(&path));
+        io.add("a.test", "");
+        io.add("b.test", "");
+        io.set_blocked("b.test", true);
+        let entities: Vec<Entity> = ["a.test", "b.test"]
+            .into_iter()
+            .map(|asset| {
+                let mut loader = Loader::new();
+                loader.enqueue(asset);
+                loader.submit();
+                app.world.spawn().insert(loader).id()
+            })
+            .collect();
+
+        // The file is only written once all batches completed
+        update_until(&mut app, |world| {
+            world.get::<Loader>(entities[0]).unwrap().is_done()
+        });
+        assert_eq!(app.world.get_resource::<LoadTimings>().unwrap().len(), 1);
+        assert!(!path.exists());
+        io.set_blocked("b.test", false);
+        update_until(&mut app, |world| {
+            world.get::<Loader>(entities[1]).unwrap().is_done()
+        });
+        let timings = LoadTimings::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(timings.len(), 2);
+        assert_eq!(&timings, app.world.get_resource::<LoadTimings>().unwrap());
+    }
+}